@@ -0,0 +1,254 @@
+//! The card structure with its rank and suit.
+//!
+//! A card is identified on the wire by an id between 0 and 416 (8 decks of 52 cards).
+//! The id modulo 52 is the index of the card face into the client textures array:
+//! the number cards first (2 to 9, four suits each), then the ten-value cards
+//! (10, jack, queen and king, grouped by suit) and finally the aces.
+
+/// The four suits of a deck, in the textures order.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+/// The thirteen ranks of a deck, from the lowest to the highest.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+/// One card of the shoe. The deck id is the deck the card comes from,
+/// so two cards with the same rank and suit can still be told apart.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+    pub deck_id: u8,
+}
+
+pub const ONE_DECK_CARDS_AMOUNT: u16 = 52;
+
+const SUITS: [Suit; 4] = [
+    Suit::Clubs,
+    Suit::Diamonds,
+    Suit::Hearts,
+    Suit::Spades,
+];
+
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+const TEN_POINTS_CARDS_START_INDEX: u16 = 32;
+const ACE_CARDS_START_INDEX: u16 = 48;
+const SUITS_AMOUNT: u16 = 4;
+
+impl Rank {
+
+    /// Returns the points of the rank. Aces are worth 11 here,
+    /// the hand is responsible for counting them as 1 when required.
+    pub fn points(self) -> u8 {
+
+        const TEN_VALUE_CARDS_POINTS_AMOUNT: u8 = 10;
+        const ACE_CARDS_POINTS_AMOUNT: u8 = 11;
+        const MINIMUM_CARD_VALUE: u8 = 2;
+
+        match self {
+            Rank::Ace => ACE_CARDS_POINTS_AMOUNT,
+            Rank::Ten |
+            Rank::Jack |
+            Rank::Queen |
+            Rank::King => TEN_VALUE_CARDS_POINTS_AMOUNT,
+            _ => self as u8 + MINIMUM_CARD_VALUE,
+        }
+    }
+}
+
+impl Card {
+
+    /// Creates a card from its wire id, returns nothing if the id is out of the shoe.
+    ///
+    /// # Args:
+    ///
+    /// `id` - the card id, between 0 and 416
+    pub fn from_wire_id(id: u16) -> Option<Card> {
+
+        const MAX_CARD_ID: u16 = 416;
+        if id >= MAX_CARD_ID {
+            return None;
+        }
+
+        let index = id % ONE_DECK_CARDS_AMOUNT;
+
+        let (rank, suit) = if index < TEN_POINTS_CARDS_START_INDEX {
+            (
+                RANKS[(index / SUITS_AMOUNT) as usize],
+                SUITS[(index % SUITS_AMOUNT) as usize],
+            )
+        }
+        else if index < ACE_CARDS_START_INDEX {
+
+            const TEN_VALUE_RANKS_START_INDEX: u16 = 8;
+            let index = index - TEN_POINTS_CARDS_START_INDEX;
+            (
+                RANKS[(TEN_VALUE_RANKS_START_INDEX + index % SUITS_AMOUNT) as usize],
+                SUITS[(index / SUITS_AMOUNT) as usize],
+            )
+        } else {
+            (
+                Rank::Ace,
+                SUITS[(index - ACE_CARDS_START_INDEX) as usize],
+            )
+        };
+
+        Some(
+            Card {
+                rank,
+                suit,
+                deck_id: (id / ONE_DECK_CARDS_AMOUNT) as u8,
+            }
+        )
+    }
+
+    /// Returns the index of the card face, between 0 and 52, used to pick the card texture.
+    pub fn face_index(&self) -> u16 {
+
+        let rank = self.rank as u16;
+        let suit = self.suit as u16;
+
+        match self.rank {
+            Rank::Ace => ACE_CARDS_START_INDEX + suit,
+            Rank::Ten |
+            Rank::Jack |
+            Rank::Queen |
+            Rank::King => {
+                TEN_POINTS_CARDS_START_INDEX +
+                    suit * SUITS_AMOUNT +
+                    rank - Rank::Ten as u16
+            },
+            _ => rank * SUITS_AMOUNT + suit,
+        }
+    }
+
+    /// Returns the wire id of the card, between 0 and 416.
+    pub fn wire_id(&self) -> u16 {
+        self.deck_id as u16 * ONE_DECK_CARDS_AMOUNT + self.face_index()
+    }
+
+    /// Returns the points of the card (aces are worth 11).
+    pub fn points(&self) -> u8 {
+        self.rank.points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_wire_id_round_trip() {
+
+        const MAX_CARD_ID: u16 = 416;
+
+        for id in 0..MAX_CARD_ID {
+
+            let card = Card::from_wire_id(id).unwrap();
+
+            assert_eq!(card.wire_id(), id);
+            assert_eq!(card.face_index(), id % ONE_DECK_CARDS_AMOUNT);
+            assert_eq!(card.deck_id as u16, id / ONE_DECK_CARDS_AMOUNT);
+        }
+    }
+
+    #[test]
+    fn test_face_indices_are_unique_in_one_deck() {
+
+        let mut faces: Vec<(Rank, Suit)> = (0..ONE_DECK_CARDS_AMOUNT)
+            .map(|id| {
+                let card = Card::from_wire_id(id).unwrap();
+                (card.rank, card.suit)
+            })
+            .collect();
+
+        faces.sort_by_key(|(rank, suit)| (*rank, *suit as u8));
+        faces.dedup();
+
+        assert_eq!(faces.len(), ONE_DECK_CARDS_AMOUNT as usize);
+    }
+
+    #[test]
+    fn test_face_index_layout() {
+
+        let two_of_clubs = Card::from_wire_id(0).unwrap();
+        assert_eq!((two_of_clubs.rank, two_of_clubs.suit), (Rank::Two, Suit::Clubs));
+
+        let nine_of_spades = Card::from_wire_id(31).unwrap();
+        assert_eq!((nine_of_spades.rank, nine_of_spades.suit), (Rank::Nine, Suit::Spades));
+
+        let ten_of_clubs = Card::from_wire_id(32).unwrap();
+        assert_eq!((ten_of_clubs.rank, ten_of_clubs.suit), (Rank::Ten, Suit::Clubs));
+
+        let king_of_diamonds = Card::from_wire_id(39).unwrap();
+        assert_eq!((king_of_diamonds.rank, king_of_diamonds.suit), (Rank::King, Suit::Diamonds));
+
+        let ace_of_spades = Card::from_wire_id(51).unwrap();
+        assert_eq!((ace_of_spades.rank, ace_of_spades.suit), (Rank::Ace, Suit::Spades));
+
+        let ace_of_spades_of_last_deck = Card::from_wire_id(415).unwrap();
+        assert_eq!(ace_of_spades_of_last_deck.rank, Rank::Ace);
+        assert_eq!(ace_of_spades_of_last_deck.suit, Suit::Spades);
+        assert_eq!(ace_of_spades_of_last_deck.deck_id, 7);
+    }
+
+    #[test]
+    fn test_invalid_wire_ids() {
+        assert_eq!(Card::from_wire_id(416), None);
+        assert_eq!(Card::from_wire_id(1000), None);
+        assert_eq!(Card::from_wire_id(u16::MAX), None);
+    }
+
+    #[test]
+    fn test_points() {
+        assert_eq!(Rank::Two.points(), 2);
+        assert_eq!(Rank::Nine.points(), 9);
+        assert_eq!(Rank::Ten.points(), 10);
+        assert_eq!(Rank::Jack.points(), 10);
+        assert_eq!(Rank::Queen.points(), 10);
+        assert_eq!(Rank::King.points(), 10);
+        assert_eq!(Rank::Ace.points(), 11);
+
+        for id in 0..ONE_DECK_CARDS_AMOUNT {
+
+            let card = Card::from_wire_id(id).unwrap();
+            assert_eq!(card.points(), card.rank.points());
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! The commitment of the shuffled order of a shoe, published by the server before the first card is dealt,
+//! and the commitment of the seed of the next shoe, published before the players send their own seeds.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+use card::Card;
+use shuffle_strategy::{
+    RandomShuffle,
+    ShuffleStrategy,
+};
+
+/// Returns the hexadecimal representation of the given hash.
+///
+/// # Args:
+///
+/// `hash` - the bytes of the hash
+fn to_hex(hash: &[u8]) -> String {
+    hash.iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Returns the SHA-256 hash (hexadecimal) of the given cards order followed by the server seed.
+/// The server publishes it before dealing the shoe and reveals the seed and the order at the next shuffle,
+/// so the players can check the cards have been dealt in the committed order. The secret seed prevents
+/// the players from guessing the remaining cards from the commitment.
+///
+/// # Args:
+///
+/// `cards` - the wire ids of the cards of the shoe, the first one is drawn first
+/// `server_seed` - the secret seed of the server, revealed with the order
+pub fn shoe_commitment(
+    cards: &[u16],
+    server_seed: &str,
+) -> String {
+
+    let mut hasher = Sha256::new();
+
+    for card in cards {
+        hasher.update(card.to_be_bytes());
+    }
+
+    hasher.update(server_seed.as_bytes());
+
+    to_hex(&hasher.finalize())
+}
+
+/// Returns the SHA-256 hash (hexadecimal) of the given salt followed by the seed of a shoe.
+/// The server publishes it before the players send the seeds mixed into the shuffle of that shoe,
+/// so the shoe seed cannot be chosen according to their seeds. The secret salt prevents
+/// the players from finding the shoe seed from the commitment.
+///
+/// # Args:
+///
+/// `shoe_seed` - the seed of the shoe
+/// `salt` - the secret salt of the server, revealed with the shoe seed
+pub fn seed_commitment(
+    shoe_seed: u64,
+    salt: &str,
+) -> String {
+
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(shoe_seed.to_be_bytes());
+
+    to_hex(&hasher.finalize())
+}
+
+/// Returns the seed of the generator shuffling a shoe: the SHA-256 hash of the shoe seed followed by
+/// the seeds sent by the players. Both are revealed with the order, so every player can check
+/// his own seed has been mixed into the shuffle.
+///
+/// # Args:
+///
+/// `shoe_seed` - the seed of the shoe, drawn by the server
+/// `client_seeds` - the seeds sent by the players since the previous shuffle
+pub fn shuffle_seed(
+    shoe_seed: u64,
+    client_seeds: &[String],
+) -> [u8; 32] {
+
+    let mut hasher = Sha256::new();
+    hasher.update(shoe_seed.to_be_bytes());
+
+    /* every seed is prefixed with its length, so the seeds cannot be split differently */
+    for client_seed in client_seeds {
+        hasher.update((client_seed.len() as u64).to_be_bytes());
+        hasher.update(client_seed.as_bytes());
+    }
+
+    hasher.finalize().into()
+}
+
+/// Returns the order of the given cards once randomly shuffled with the seed of a shoe mixed with the seeds
+/// of the players, as the server does with the random strategies. The shuffled order does not depend
+/// on the order of the given cards, so a player recomputes the revealed order from the revealed seeds.
+///
+/// # Args:
+///
+/// `order` - the wire ids of the cards of the shoe, the first one is drawn first
+/// `shoe_seed` - the seed of the shoe
+/// `client_seeds` - the seeds sent by the players since the previous shuffle
+pub fn random_shuffle_order(
+    order: &[u16],
+    shoe_seed: u64,
+    client_seeds: &[String],
+) -> Vec<u16> {
+
+    let mut cards: Vec<Card> = order.iter()
+        .filter_map(|wire_id| Card::from_wire_id(*wire_id))
+        .collect();
+
+    let mut rng = StdRng::from_seed(shuffle_seed(
+        shoe_seed,
+        client_seeds,
+    ));
+    RandomShuffle.shuffle(&mut cards, &mut rng);
+
+    /* the last card of the shoe is drawn first */
+    cards.iter()
+        .rev()
+        .map(Card::wire_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn seeds(values: &[&str]) -> Vec<String> {
+        values.iter()
+            .map(|value| value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_shoe_commitment_is_deterministic() {
+        assert_eq!(
+            shoe_commitment(&[0, 1, 2], "seed"),
+            shoe_commitment(&[0, 1, 2], "seed"),
+        );
+        assert_eq!(shoe_commitment(&[0, 1, 2], "seed").len(), 64);
+    }
+
+    #[test]
+    fn test_shoe_commitment_depends_on_every_input() {
+
+        let commitment = shoe_commitment(&[0, 1, 2], "seed");
+
+        assert_ne!(commitment, shoe_commitment(&[0, 2, 1], "seed"));
+        assert_ne!(commitment, shoe_commitment(&[0, 1, 3], "seed"));
+        assert_ne!(commitment, shoe_commitment(&[0, 1], "seed"));
+        assert_ne!(commitment, shoe_commitment(&[0, 1, 2], "seeds"));
+    }
+
+    #[test]
+    fn test_seed_commitment_depends_on_every_input() {
+
+        let commitment = seed_commitment(42, "salt");
+
+        assert_eq!(commitment, seed_commitment(42, "salt"));
+        assert_ne!(commitment, seed_commitment(43, "salt"));
+        assert_ne!(commitment, seed_commitment(42, "pepper"));
+    }
+
+    #[test]
+    fn test_shuffle_seed_is_deterministic() {
+        assert_eq!(
+            shuffle_seed(42, &seeds(&["alice", "bob"])),
+            shuffle_seed(42, &seeds(&["alice", "bob"])),
+        );
+    }
+
+    #[test]
+    fn test_shuffle_seed_depends_on_every_input() {
+
+        let seed = shuffle_seed(42, &seeds(&["alice", "bob"]));
+
+        assert_ne!(seed, shuffle_seed(43, &seeds(&["alice", "bob"])));
+        assert_ne!(seed, shuffle_seed(42, &seeds(&["alice", "bobby"])));
+        assert_ne!(seed, shuffle_seed(42, &seeds(&["bob", "alice"])));
+        assert_ne!(seed, shuffle_seed(42, &seeds(&["alice"])));
+        assert_ne!(seed, shuffle_seed(42, &[]));
+    }
+
+    #[test]
+    fn test_shuffle_seed_does_not_concatenate_the_client_seeds() {
+        assert_ne!(
+            shuffle_seed(42, &seeds(&["ab", "c"])),
+            shuffle_seed(42, &seeds(&["a", "bc"])),
+        );
+    }
+
+    #[test]
+    fn test_random_shuffle_order_does_not_depend_on_the_given_order() {
+
+        let order: Vec<u16> = (0..52).collect();
+        let reversed_order: Vec<u16> = order.iter()
+            .rev()
+            .cloned()
+            .collect();
+
+        let shuffled_order = random_shuffle_order(&order, 42, &seeds(&["alice"]));
+        assert_eq!(shuffled_order, random_shuffle_order(&reversed_order, 42, &seeds(&["alice"])));
+        assert_ne!(shuffled_order, random_shuffle_order(&order, 43, &seeds(&["alice"])));
+        assert_ne!(shuffled_order, random_shuffle_order(&order, 42, &seeds(&["bob"])));
+
+        let mut sorted_order = shuffled_order.clone();
+        sorted_order.sort();
+        assert_eq!(sorted_order, order);
+    }
+}
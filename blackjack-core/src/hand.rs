@@ -0,0 +1,289 @@
+//! The hand structure, evaluating the points of a set of cards.
+
+use card::{
+    Card,
+    Rank,
+};
+
+const MAX_HAND_POINTS: u8 = 21;
+
+/// Contains the cards of one hand, of a player or of the bank.
+///
+/// Aces are counted as 11 as long as the hand does not bust,
+/// so they are re-valued every time a card is added.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Hand {
+    cards: Vec<Card>,
+    from_split: bool,
+}
+
+impl Hand {
+
+    /// Creates a new empty hand.
+    pub fn new() -> Hand {
+        Hand {
+            cards: vec![],
+            from_split: false,
+        }
+    }
+
+    /// Creates a hand already containing the given cards.
+    ///
+    /// # Args:
+    ///
+    /// `cards` - the cards of the hand
+    /// `from_split` - true if the hand comes from a split
+    pub fn from_cards(
+        cards: Vec<Card>,
+        from_split: bool,
+    ) -> Hand {
+        Hand {
+            cards,
+            from_split,
+        }
+    }
+
+    /// Adds one card to the hand.
+    ///
+    /// # Args:
+    ///
+    /// `card` - the card to add
+    pub fn add(
+        &mut self,
+        card: Card,
+    ) {
+        self.cards.push(card);
+    }
+
+    /// Removes all the cards of the hand.
+    pub fn clear(&mut self) {
+        self.cards.clear();
+        self.from_split = false;
+    }
+
+    /// Splits the hand: the second card is moved into a new hand which is returned.
+    /// Both hands are then considered as split hands.
+    pub fn split(&mut self) -> Hand {
+
+        self.from_split = true;
+
+        Hand {
+            cards: self.cards.pop().into_iter().collect(),
+            from_split: true,
+        }
+    }
+
+    /// Indicates if the hand comes from a split.
+    pub fn is_split(&self) -> bool {
+        self.from_split
+    }
+
+    /// Indicates if the hand comes from the split of a pair of aces.
+    pub fn is_split_aces(&self) -> bool {
+        self.from_split &&
+            self.cards
+                .first()
+                .is_some_and(|card| card.rank == Rank::Ace)
+    }
+
+    /// Indicates if the hand is made of two cards with the same points.
+    pub fn is_pair(&self) -> bool {
+
+        const PAIR_CARDS_AMOUNT: usize = 2;
+
+        self.cards.len() == PAIR_CARDS_AMOUNT &&
+            self.cards[0].points() == self.cards[1].points()
+    }
+
+    /// Returns the cards of the hand, in the order they have been drawn.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Returns the amount of cards into the hand.
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Indicates if the hand has no card.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Returns the hand points, all the aces being counted as 1.
+    pub fn hard_total(&self) -> u8 {
+
+        const ACE_CARDS_HARD_POINTS_AMOUNT: u8 = 1;
+
+        self.cards
+            .iter()
+            .map(|card| {
+                if card.rank == Rank::Ace {
+                    ACE_CARDS_HARD_POINTS_AMOUNT
+                } else {
+                    card.points()
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the hand points, one ace being counted as 11 if it does not make the hand bust.
+    /// This is the value of the hand, equal to the hard total when the hand is not soft.
+    pub fn soft_total(&self) -> u8 {
+
+        const ACE_CARDS_SOFT_POINTS_BONUS: u8 = 10;
+
+        let hard_total = self.hard_total();
+
+        if self.has_ace() &&
+            hard_total + ACE_CARDS_SOFT_POINTS_BONUS <= MAX_HAND_POINTS {
+            return hard_total + ACE_CARDS_SOFT_POINTS_BONUS;
+        }
+
+        hard_total
+    }
+
+    /// Indicates if one ace of the hand is currently counted as 11.
+    pub fn is_soft(&self) -> bool {
+        self.soft_total() != self.hard_total()
+    }
+
+    /// Indicates if the hand is a natural: 21 points with the first two cards.
+    /// A split hand is never a natural.
+    pub fn is_blackjack(&self) -> bool {
+
+        const BLACKJACK_CARDS_AMOUNT: usize = 2;
+
+        !self.from_split &&
+            self.cards.len() == BLACKJACK_CARDS_AMOUNT &&
+            self.soft_total() == MAX_HAND_POINTS
+    }
+
+    /// Indicates if the hand goes over 21 points.
+    pub fn is_bust(&self) -> bool {
+        self.hard_total() > MAX_HAND_POINTS
+    }
+
+    /// Indicates if the hand contains at least one ace.
+    fn has_ace(&self) -> bool {
+        self.cards
+            .iter()
+            .any(|card| card.rank == Rank::Ace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use card::Suit;
+
+    /// Returns the hand made of the cards of the given ranks.
+    ///
+    /// # Args:
+    ///
+    /// `ranks` - the ranks of the cards of the hand
+    fn hand_of(ranks: &[Rank]) -> Hand {
+        Hand::from_cards(
+            ranks.iter()
+                .map(|rank| Card {
+                    rank: *rank,
+                    suit: Suit::Hearts,
+                    deck_id: 0,
+                })
+                .collect(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_two_aces_and_a_nine() {
+
+        let hand = hand_of(&[Rank::Ace, Rank::Ace, Rank::Nine]);
+
+        assert_eq!(hand.hard_total(), 11);
+        assert_eq!(hand.soft_total(), 21);
+        assert!(hand.is_soft());
+        assert!(!hand.is_blackjack());
+        assert!(!hand.is_bust());
+    }
+
+    #[test]
+    fn test_soft_and_hard_seventeen() {
+
+        let soft_seventeen = hand_of(&[Rank::Ace, Rank::Six]);
+
+        assert_eq!(soft_seventeen.soft_total(), 17);
+        assert_eq!(soft_seventeen.hard_total(), 7);
+        assert!(soft_seventeen.is_soft());
+
+        let hard_seventeen = hand_of(&[Rank::Ten, Rank::Seven]);
+
+        assert_eq!(hard_seventeen.soft_total(), 17);
+        assert_eq!(hard_seventeen.hard_total(), 17);
+        assert!(!hard_seventeen.is_soft());
+
+        /* the ace is counted as 1 once an 11 would make the hand bust */
+        let mut hardened_seventeen = hand_of(&[Rank::Ace, Rank::Six]);
+        hardened_seventeen.add(Card {
+            rank: Rank::Ten,
+            suit: Suit::Clubs,
+            deck_id: 0,
+        });
+
+        assert_eq!(hardened_seventeen.soft_total(), 17);
+        assert!(!hardened_seventeen.is_soft());
+    }
+
+    #[test]
+    fn test_natural_and_split_twenty_one() {
+
+        let natural = hand_of(&[Rank::Ace, Rank::King]);
+
+        assert!(natural.is_blackjack());
+
+        let mut split_aces = hand_of(&[Rank::Ace, Rank::Ace]);
+        let mut second_hand = split_aces.split();
+
+        split_aces.add(Card {
+            rank: Rank::King,
+            suit: Suit::Spades,
+            deck_id: 0,
+        });
+        second_hand.add(Card {
+            rank: Rank::Queen,
+            suit: Suit::Spades,
+            deck_id: 0,
+        });
+
+        for hand in &[split_aces, second_hand] {
+            assert_eq!(hand.soft_total(), 21);
+            assert!(hand.is_split_aces());
+            assert!(!hand.is_blackjack());
+        }
+
+        let three_cards_twenty_one = hand_of(&[Rank::Seven, Rank::Seven, Rank::Seven]);
+
+        assert_eq!(three_cards_twenty_one.soft_total(), 21);
+        assert!(!three_cards_twenty_one.is_blackjack());
+    }
+
+    #[test]
+    fn test_busts() {
+
+        let bust = hand_of(&[Rank::King, Rank::Queen, Rank::Two]);
+
+        assert_eq!(bust.hard_total(), 22);
+        assert!(bust.is_bust());
+
+        let soft_bust_avoided = hand_of(&[Rank::Ace, Rank::King, Rank::Queen]);
+
+        assert_eq!(soft_bust_avoided.soft_total(), 21);
+        assert!(!soft_bust_avoided.is_bust());
+
+        let aces_bust = hand_of(&[Rank::Ace, Rank::Ace, Rank::King, Rank::Queen]);
+
+        assert_eq!(aces_bust.hard_total(), 22);
+        assert!(aces_bust.is_bust());
+    }
+}
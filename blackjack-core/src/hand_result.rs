@@ -0,0 +1,98 @@
+//! The result of one player hand at the end of a round, decided by the server.
+
+use hand::Hand;
+use rules::BlackjackPayout;
+
+/// The result of one player hand against the bank hand.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HandResult {
+    Win,
+    Loss,
+    Push,
+    Blackjack,
+    Surrender,
+    Bust,
+}
+
+impl HandResult {
+
+    /// Returns the result of the given player hand against the final bank hand.
+    /// A natural beats any other 21, and a busted hand loses even if the bank busts too.
+    ///
+    /// # Args:
+    ///
+    /// `hand` - the player hand
+    /// `bank_hand` - the final bank hand
+    /// `surrendered` - true if the player surrendered the hand
+    pub fn of(
+        hand: &Hand,
+        bank_hand: &Hand,
+        surrendered: bool,
+    ) -> HandResult {
+
+        if surrendered {
+            return HandResult::Surrender;
+        }
+
+        if hand.is_bust() {
+            return HandResult::Bust;
+        }
+
+        if hand.is_blackjack() || bank_hand.is_blackjack() {
+            return match (hand.is_blackjack(), bank_hand.is_blackjack()) {
+                (true, true) => HandResult::Push,
+                (true, false) => HandResult::Blackjack,
+                _ => HandResult::Loss,
+            };
+        }
+
+        if bank_hand.is_bust() {
+            return HandResult::Win;
+        }
+
+        let points = hand.soft_total();
+        let bank_points = bank_hand.soft_total();
+
+        if points > bank_points {
+            HandResult::Win
+        } else if points < bank_points {
+            HandResult::Loss
+        } else {
+            HandResult::Push
+        }
+    }
+
+    /// Returns the amount won or lost by the hand for the given stake (the stake itself excluded).
+    /// A surrendered hand only loses half of its stake.
+    ///
+    /// # Args:
+    ///
+    /// `stake` - the amount bet on the hand
+    /// `payout` - the payout of a natural blackjack at the table
+    pub fn amount(
+        &self,
+        stake: u32,
+        payout: BlackjackPayout,
+    ) -> u32 {
+        match self {
+            HandResult::Win |
+            HandResult::Loss |
+            HandResult::Bust => stake,
+            HandResult::Push => 0,
+            HandResult::Blackjack => payout.winnings(stake),
+            HandResult::Surrender => stake / 2,
+        }
+    }
+
+    /// Indicates if the amount of the result is lost by the player.
+    pub fn is_loss(&self) -> bool {
+        match self {
+            HandResult::Loss |
+            HandResult::Bust |
+            HandResult::Surrender => true,
+            HandResult::Win |
+            HandResult::Push |
+            HandResult::Blackjack => false,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Cards, hands, hand results, table rules, table summaries, shuffle strategies and shoe commitments
+//! shared by the server and the client.
+
+extern crate rand;
+extern crate serde;
+extern crate sha2;
+#[macro_use] extern crate serde_derive;
+
+mod card;
+mod commitment;
+mod hand;
+mod hand_result;
+mod rules;
+mod shoe;
+mod shuffle_strategy;
+mod table_summary;
+
+pub use card::{
+    Card,
+    Rank,
+    Suit,
+};
+pub use commitment::{
+    random_shuffle_order,
+    seed_commitment,
+    shoe_commitment,
+    shuffle_seed,
+};
+pub use hand::Hand;
+pub use hand_result::HandResult;
+pub use rules::{
+    BlackjackPayout,
+    DoubleDownRule,
+    SurrenderRule,
+    TableRules,
+};
+pub use shoe::Shoe;
+pub use shuffle_strategy::{
+    ContinuousShuffle,
+    HandShuffle,
+    RandomShuffle,
+    ScriptedShuffle,
+    ShuffleStrategy,
+};
+pub use table_summary::TableSummary;
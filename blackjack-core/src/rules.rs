@@ -0,0 +1,317 @@
+//! The table rules, shared by the server and the clients so both sides obey the same rules.
+
+use card::Card;
+use hand::Hand;
+
+/// The payout of a player natural blackjack.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BlackjackPayout {
+    ThreeToTwo,
+    SixToFive,
+}
+
+/// The hands the player is allowed to double down on.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DoubleDownRule {
+    AnyTwoCards,
+    NineToEleven,
+}
+
+/// When the player is allowed to surrender, if ever.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SurrenderRule {
+    Forbidden,
+    Late,
+    Early,
+}
+
+/// Contains all the configurable rules of one table.
+/// Any rule missing from a rules file takes its default value.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct TableRules {
+    pub seats_amount: u8,
+    pub decks_amount: u8,
+    pub dealer_hits_soft_17: bool,
+    pub blackjack_payout: BlackjackPayout,
+    pub double_down: DoubleDownRule,
+    pub double_after_split: bool,
+    pub max_split_hands: u8,
+    pub resplit_aces: bool,
+    pub surrender: SurrenderRule,
+    pub penetration: f32,
+    pub min_bet: u32,
+    pub max_bet: u32,
+    pub starting_bankroll: u32,
+    pub decision_time: u16,
+}
+
+impl BlackjackPayout {
+
+    /// Returns the amount won by a natural blackjack for the given stake (the stake itself excluded),
+    /// saturated at the maximum amount instead of overflowing.
+    ///
+    /// # Args:
+    ///
+    /// `stake` - the amount bet on the hand
+    pub fn winnings(
+        &self,
+        stake: u32,
+    ) -> u32 {
+        match self {
+            BlackjackPayout::ThreeToTwo => stake.saturating_mul(3) / 2,
+            BlackjackPayout::SixToFive => stake.saturating_mul(6) / 5,
+        }
+    }
+
+    /// Returns the payout as displayed on the table.
+    pub fn label(&self) -> &'static str {
+        match self {
+            BlackjackPayout::ThreeToTwo => "3:2",
+            BlackjackPayout::SixToFive => "6:5",
+        }
+    }
+}
+
+impl Default for TableRules {
+
+    /// Returns the rules of the original game: 8 decks, dealer stands on soft 17,
+    /// blackjack pays 3:2 and the player can double down on any two cards.
+    /// The player starts with 1000 and bets between 10 and 500 per round, up to 5 players sit at the table.
+    /// The players have 30 seconds to take every decision.
+    fn default() -> TableRules {
+
+        const DEFAULT_SEATS_AMOUNT: u8 = 5;
+        const DEFAULT_DECKS_AMOUNT: u8 = 8;
+        const DEFAULT_MAX_SPLIT_HANDS: u8 = 4;
+        const DEFAULT_PENETRATION: f32 = 0.75;
+        const DEFAULT_MIN_BET: u32 = 10;
+        const DEFAULT_MAX_BET: u32 = 500;
+        const DEFAULT_STARTING_BANKROLL: u32 = 1000;
+        const DEFAULT_DECISION_TIME: u16 = 30;
+
+        TableRules {
+            seats_amount: DEFAULT_SEATS_AMOUNT,
+            decks_amount: DEFAULT_DECKS_AMOUNT,
+            dealer_hits_soft_17: false,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            double_down: DoubleDownRule::AnyTwoCards,
+            double_after_split: true,
+            max_split_hands: DEFAULT_MAX_SPLIT_HANDS,
+            resplit_aces: false,
+            surrender: SurrenderRule::Forbidden,
+            penetration: DEFAULT_PENETRATION,
+            min_bet: DEFAULT_MIN_BET,
+            max_bet: DEFAULT_MAX_BET,
+            starting_bankroll: DEFAULT_STARTING_BANKROLL,
+            decision_time: DEFAULT_DECISION_TIME,
+        }
+    }
+}
+
+impl TableRules {
+
+    /// Checks the rules values are consistent, returns the reason of the first invalid rule otherwise.
+    /// The amounts are bounded so the bankrolls and the payouts stay far from the integer limits,
+    /// the rules of the tables opened by the players are not trusted.
+    pub fn validate(&self) -> Result<(), &'static str> {
+
+        const MIN_SEATS_AMOUNT: u8 = 1;
+        const MAX_SEATS_AMOUNT: u8 = 7;
+        const MIN_DECKS_AMOUNT: u8 = 1;
+        const MAX_DECKS_AMOUNT: u8 = 8;
+        const MIN_SPLIT_HANDS: u8 = 1;
+        const MAX_SPLIT_HANDS: u8 = 4;
+        const MAX_BET: u32 = 100_000;
+        const MAX_STARTING_BANKROLL: u32 = 1_000_000;
+        const MAX_DECISION_TIME: u16 = 300;
+
+        if self.seats_amount < MIN_SEATS_AMOUNT ||
+            self.seats_amount > MAX_SEATS_AMOUNT {
+            return Err("the seats amount must be between 1 and 7");
+        }
+
+        if self.decks_amount < MIN_DECKS_AMOUNT ||
+            self.decks_amount > MAX_DECKS_AMOUNT {
+            return Err("the decks amount must be between 1 and 8");
+        }
+
+        if self.max_split_hands < MIN_SPLIT_HANDS ||
+            self.max_split_hands > MAX_SPLIT_HANDS {
+            return Err("the maximum split hands amount must be between 1 and 4");
+        }
+
+        if self.penetration <= 0.0 || self.penetration > 1.0 {
+            return Err("the penetration must be between 0 (excluded) and 1");
+        }
+
+        if self.min_bet == 0 || self.min_bet > self.max_bet {
+            return Err("the minimum bet must be positive and lower than the maximum bet");
+        }
+
+        if self.max_bet > MAX_BET {
+            return Err("the maximum bet must be at most 100000");
+        }
+
+        if self.starting_bankroll > MAX_STARTING_BANKROLL {
+            return Err("the starting bankroll must be at most 1000000");
+        }
+
+        if self.decision_time > MAX_DECISION_TIME {
+            return Err("the decision time must be at most 300 seconds");
+        }
+
+        Ok(())
+    }
+
+    /// Indicates if the given bet respects the table limits.
+    ///
+    /// # Args:
+    ///
+    /// `amount` - the amount of the bet
+    pub fn is_valid_bet(
+        &self,
+        amount: u32,
+    ) -> bool {
+        amount >= self.min_bet &&
+            amount <= self.max_bet
+    }
+
+    /// Indicates if the dealer has to draw one more card according to his current hand.
+    ///
+    /// # Args:
+    ///
+    /// `bank_hand` - the current dealer hand
+    pub fn dealer_must_hit(
+        &self,
+        bank_hand: &Hand,
+    ) -> bool {
+
+        const DEALER_STANDING_POINTS: u8 = 17;
+
+        let points = bank_hand.soft_total();
+
+        points < DEALER_STANDING_POINTS || (
+            self.dealer_hits_soft_17 &&
+            points == DEALER_STANDING_POINTS &&
+            bank_hand.is_soft()
+        )
+    }
+
+    /// Indicates if the dealer has to check his hole card for a blackjack,
+    /// which is the case when his up card is an ace or a ten-value card.
+    ///
+    /// # Args:
+    ///
+    /// `up_card` - the dealer face-up card
+    pub fn dealer_peeks(
+        &self,
+        up_card: &Card,
+    ) -> bool {
+
+        const MIN_PEEKING_UP_CARD_POINTS: u8 = 10;
+        up_card.points() >= MIN_PEEKING_UP_CARD_POINTS
+    }
+
+    /// Indicates if the player is allowed to double down on the given hand.
+    ///
+    /// # Args:
+    ///
+    /// `hand` - the player hand
+    pub fn can_double(
+        &self,
+        hand: &Hand,
+    ) -> bool {
+
+        const REQUIRED_CARDS_AMOUNT_FOR_DOUBLE: usize = 2;
+
+        if hand.len() != REQUIRED_CARDS_AMOUNT_FOR_DOUBLE ||
+            hand.is_split_aces() ||
+            hand.is_split() && !self.double_after_split {
+            return false;
+        }
+
+        match self.double_down {
+            DoubleDownRule::AnyTwoCards => true,
+            DoubleDownRule::NineToEleven => {
+                !hand.is_soft() &&
+                    (9..=11).contains(&hand.hard_total())
+            },
+        }
+    }
+
+    /// Indicates if the player is allowed to surrender the given hand,
+    /// which is only possible as the first decision on the two first cards.
+    ///
+    /// # Args:
+    ///
+    /// `hand` - the player hand
+    pub fn can_surrender(
+        &self,
+        hand: &Hand,
+    ) -> bool {
+
+        const REQUIRED_CARDS_AMOUNT_FOR_SURRENDER: usize = 2;
+
+        self.surrender != SurrenderRule::Forbidden &&
+            hand.len() == REQUIRED_CARDS_AMOUNT_FOR_SURRENDER &&
+            !hand.is_split()
+    }
+
+    /// Indicates if the player is allowed to draw one more card on the given hand.
+    /// Hands with 21 points or more cannot be hit, and split aces only receive one card.
+    ///
+    /// # Args:
+    ///
+    /// `hand` - the player hand
+    pub fn can_hit(
+        &self,
+        hand: &Hand,
+    ) -> bool {
+
+        const MAX_HAND_POINTS: u8 = 21;
+        const SPLIT_ACES_MAX_CARDS_AMOUNT: usize = 2;
+
+        if hand.is_split_aces() &&
+            hand.len() >= SPLIT_ACES_MAX_CARDS_AMOUNT {
+            return false;
+        }
+
+        hand.soft_total() < MAX_HAND_POINTS
+    }
+
+    /// Indicates if the player is allowed to split the given hand.
+    ///
+    /// # Args:
+    ///
+    /// `hand` - the player hand
+    /// `hands_amount` - the current amount of hands of the player
+    pub fn can_split(
+        &self,
+        hand: &Hand,
+        hands_amount: usize,
+    ) -> bool {
+
+        if !hand.is_pair() ||
+            hands_amount >= self.max_split_hands as usize {
+            return false;
+        }
+
+        !hand.is_split_aces() || self.resplit_aces
+    }
+
+    /// Indicates if the player still has a decision to take on the given hand.
+    ///
+    /// # Args:
+    ///
+    /// `hand` - the player hand
+    /// `hands_amount` - the current amount of hands of the player
+    pub fn is_playable(
+        &self,
+        hand: &Hand,
+        hands_amount: usize,
+    ) -> bool {
+        self.can_hit(hand) ||
+            self.can_split(hand, hands_amount)
+    }
+}
@@ -0,0 +1,121 @@
+//! The shoe containing all the cards of the decks used for the game.
+
+use rand::RngCore;
+
+use card::{
+    Card,
+    ONE_DECK_CARDS_AMOUNT,
+};
+use shuffle_strategy::ShuffleStrategy;
+
+/// Contains all the cards that can still be drawn, the last card of the vector is the next one,
+/// and the discarded cards waiting for the next shuffle. The cut card is placed according to the
+/// penetration, it is only represented by the amount of remaining cards when it is reached.
+///
+/// NOTE: there are many more optimized ways to store the cards (memory and time complexity), but
+/// we voluntarily keep a raw array to store them all in order to create a genuine black-jack game situation
+pub struct Shoe {
+    cards: Vec<Card>,
+    discards: Vec<Card>,
+    cut_card_position: usize,
+}
+
+impl Shoe {
+
+    /// Creates a new ordered shoe with the given amount of decks.
+    ///
+    /// # Args:
+    ///
+    /// `decks_amount` - the amount of 52 cards decks into the shoe
+    /// `penetration` - the part of the shoe dealt before the cut card is reached
+    pub fn new(
+        decks_amount: u8,
+        penetration: f32,
+    ) -> Shoe {
+
+        let max_card_id = decks_amount as u16 * ONE_DECK_CARDS_AMOUNT;
+
+        let cards: Vec<Card> = (0..max_card_id)
+            .filter_map(Card::from_wire_id)
+            .collect();
+
+        let dealt_cards_amount = (cards.len() as f32 * penetration) as usize;
+        let cut_card_position = cards.len() - dealt_cards_amount.min(cards.len());
+
+        Shoe {
+            cards,
+            discards: vec![],
+            cut_card_position,
+        }
+    }
+
+    /// Puts the discarded cards back on top of the shoe and shuffles all the cards with the given strategy.
+    ///
+    /// # Args:
+    ///
+    /// `strategy` - the way the cards are shuffled
+    /// `rng` - the random generator used to shuffle
+    pub fn shuffle(
+        &mut self,
+        strategy: &dyn ShuffleStrategy,
+        rng: &mut dyn RngCore,
+    ) {
+        self.cards.append(&mut self.discards);
+        strategy.shuffle(&mut self.cards, rng);
+    }
+
+    /// Draws the next card of the shoe, returns nothing if the shoe is empty.
+    pub fn draw(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    /// Discards the next card of the shoe without dealing it, as done after every shuffle.
+    pub fn burn(&mut self) {
+
+        if let Some(card) = self.cards.pop() {
+            self.discards.push(card);
+        }
+    }
+
+    /// Moves the given cards to the discards, they come back into the shoe on the next shuffle.
+    ///
+    /// # Args:
+    ///
+    /// `cards` - the cards to discard
+    pub fn discard(
+        &mut self,
+        cards: &[Card],
+    ) {
+        self.discards.extend_from_slice(cards);
+    }
+
+    /// Indicates if the cut card has been reached, the shoe has to be shuffled before the next round.
+    pub fn cut_card_reached(&self) -> bool {
+        self.cards.len() <= self.cut_card_position
+    }
+
+    /// Returns the wire ids of the cards remaining into the shoe, in the drawing order.
+    pub fn order(&self) -> Vec<u16> {
+        self.cards
+            .iter()
+            .rev()
+            .map(Card::wire_id)
+            .collect()
+    }
+
+    /// Returns the amount of cards remaining into the shoe.
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns the amount of cards which can still be drawn, the discards being shuffled back
+    /// into the shoe once it is empty.
+    pub fn drawable_len(&self) -> usize {
+        self.cards.len() + self.discards.len()
+    }
+
+    /// Indicates if all the cards of the shoe have been drawn.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
@@ -0,0 +1,420 @@
+//! The shuffle strategies of the shoe, from a perfect random shuffle to a scripted order used for training.
+
+use rand::{
+    Rng,
+    RngCore,
+};
+use rand::seq::SliceRandom;
+
+use card::{
+    Card,
+    Rank,
+    Suit,
+};
+
+/// A way of shuffling all the cards of a shoe. The cards are given in the shoe order,
+/// the last card of the slice is the next one drawn.
+pub trait ShuffleStrategy {
+
+    /// Returns the name of the strategy, as logged by the server.
+    fn name(&self) -> &'static str;
+
+    /// Reorders the given cards.
+    ///
+    /// # Args:
+    ///
+    /// `cards` - all the cards of the shoe, the discards being on top of the cards which have not been dealt
+    /// `rng` - the random generator used to shuffle
+    fn shuffle(
+        &self,
+        cards: &mut [Card],
+        rng: &mut dyn RngCore,
+    );
+
+    /// Indicates if the discards go back into the shoe after every round,
+    /// the shoe is then shuffled before every round whatever the cut card.
+    fn is_continuous(&self) -> bool {
+        false
+    }
+
+    /// Indicates if the first card is burnt after every shuffle.
+    fn burns_card(&self) -> bool {
+        true
+    }
+
+    /// Indicates if the shuffled order only depends on the shuffled cards and on the random generator,
+    /// the players can then recompute it from the revealed seeds.
+    fn is_recomputable(&self) -> bool {
+        false
+    }
+}
+
+/// A perfect random shuffle. The cards are sorted first, so the shuffled order only depends
+/// on the random generator and not on the order the cards have been discarded in.
+pub struct RandomShuffle;
+
+impl ShuffleStrategy for RandomShuffle {
+
+    fn name(&self) -> &'static str {
+        "random shuffle"
+    }
+
+    fn shuffle(
+        &self,
+        cards: &mut [Card],
+        rng: &mut dyn RngCore,
+    ) {
+
+        cards.sort_by_key(Card::wire_id);
+        cards.shuffle(rng);
+    }
+
+    fn is_recomputable(&self) -> bool {
+        true
+    }
+}
+
+/// A continuous shuffling machine: the discards go back into the machine after every round,
+/// which deals from a perfectly shuffled shoe, without any burnt card.
+pub struct ContinuousShuffle;
+
+impl ShuffleStrategy for ContinuousShuffle {
+
+    fn name(&self) -> &'static str {
+        "continuous shuffle"
+    }
+
+    fn shuffle(
+        &self,
+        cards: &mut [Card],
+        rng: &mut dyn RngCore,
+    ) {
+        RandomShuffle.shuffle(cards, rng);
+    }
+
+    fn is_continuous(&self) -> bool {
+        true
+    }
+
+    fn burns_card(&self) -> bool {
+        false
+    }
+
+    fn is_recomputable(&self) -> bool {
+        true
+    }
+}
+
+/// The shuffle of a dealer by hand: a riffle, a strip, another riffle and a box.
+/// Such a shuffle is far from perfect, the clumps of cards of the previous rounds are kept
+/// into the new shoe, so the shuffled order also depends on the order the cards have been discarded in.
+pub struct HandShuffle;
+
+impl ShuffleStrategy for HandShuffle {
+
+    fn name(&self) -> &'static str {
+        "hand shuffle"
+    }
+
+    fn shuffle(
+        &self,
+        cards: &mut [Card],
+        rng: &mut dyn RngCore,
+    ) {
+
+        riffle(cards, rng);
+        strip(cards, rng);
+        riffle(cards, rng);
+        box_cut(cards);
+    }
+}
+
+/// A scripted order, the given cards are dealt first in the given order after every shuffle,
+/// followed by all the other cards sorted. The scripted cards missing from the shoe are skipped.
+pub struct ScriptedShuffle {
+    pub cards: Vec<(Rank, Suit)>,
+}
+
+impl ShuffleStrategy for ScriptedShuffle {
+
+    fn name(&self) -> &'static str {
+        "scripted order"
+    }
+
+    fn shuffle(
+        &self,
+        cards: &mut [Card],
+        _: &mut dyn RngCore,
+    ) {
+
+        cards.sort_by_key(Card::wire_id);
+
+        let mut remaining_cards = cards.to_vec();
+        let mut scripted_cards = vec![];
+
+        for (rank, suit) in &self.cards {
+
+            let position = remaining_cards
+                .iter()
+                .position(|card| card.rank == *rank && card.suit == *suit);
+
+            if let Some(position) = position {
+                scripted_cards.push(remaining_cards.remove(position));
+            }
+        }
+
+        /* the first scripted card is the last one of the shoe, so it is drawn first */
+        remaining_cards.extend(scripted_cards.iter().rev());
+        cards.copy_from_slice(&remaining_cards);
+    }
+}
+
+/// Riffles the cards: the cards are cut around the middle, then the two halves are interleaved
+/// by dropping the cards one by one from either half, the larger half being more likely to drop (Gilbert-Shannon-Reeds model).
+///
+/// # Args:
+///
+/// `cards` - the cards to riffle
+/// `rng` - the random generator used to cut and drop the cards
+fn riffle(
+    cards: &mut [Card],
+    rng: &mut dyn RngCore,
+) {
+
+    let cut_position = (0..cards.len())
+        .filter(|_| rng.gen::<bool>())
+        .count();
+
+    let (left_half, right_half) = cards.split_at(cut_position);
+
+    let mut riffled_cards = Vec::with_capacity(cards.len());
+    let mut left_index = 0;
+    let mut right_index = 0;
+
+    while riffled_cards.len() < cards.len() {
+
+        let left_remaining = left_half.len() - left_index;
+        let right_remaining = right_half.len() - right_index;
+
+        if rng.gen_range(0, left_remaining + right_remaining) < left_remaining {
+            riffled_cards.push(left_half[left_index]);
+            left_index += 1;
+        } else {
+            riffled_cards.push(right_half[right_index]);
+            right_index += 1;
+        }
+    }
+
+    cards.copy_from_slice(&riffled_cards);
+}
+
+/// Strips the cards: small packets are taken from the top one after the other and dropped onto a new pile,
+/// which reverses the order of the packets but not the order of the cards into every packet.
+///
+/// # Args:
+///
+/// `cards` - the cards to strip, the last one is the top one
+/// `rng` - the random generator used to size the packets
+fn strip(
+    cards: &mut [Card],
+    rng: &mut dyn RngCore,
+) {
+
+    const MIN_PACKET_SIZE: usize = 3;
+    const MAX_PACKET_SIZE: usize = 12;
+
+    let mut stripped_cards = Vec::with_capacity(cards.len());
+    let mut top = cards.len();
+
+    while top > 0 {
+
+        let packet_size = rng.gen_range(MIN_PACKET_SIZE, MAX_PACKET_SIZE + 1)
+            .min(top);
+
+        stripped_cards.extend_from_slice(&cards[top - packet_size..top]);
+        top -= packet_size;
+    }
+
+    cards.copy_from_slice(&stripped_cards);
+}
+
+/// Boxes the cards: the cards are cut into four blocks put back in the reverse order.
+///
+/// # Args:
+///
+/// `cards` - the cards to box
+fn box_cut(cards: &mut [Card]) {
+
+    const BLOCKS_AMOUNT: usize = 4;
+
+    let block_size = cards.len().div_ceil(BLOCKS_AMOUNT).max(1);
+
+    let boxed_cards: Vec<Card> = cards.chunks(block_size)
+        .rev()
+        .flatten()
+        .copied()
+        .collect();
+
+    cards.copy_from_slice(&boxed_cards);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use card::ONE_DECK_CARDS_AMOUNT;
+
+    const SHOE_CARDS_AMOUNT: u16 = 312;
+
+    /// Returns the cards of a shoe of six decks, sorted by wire id.
+    fn shoe_cards() -> Vec<Card> {
+        (0..SHOE_CARDS_AMOUNT)
+            .filter_map(Card::from_wire_id)
+            .collect()
+    }
+
+    /// Returns the sorted wire ids of the given cards, which tell every card apart.
+    ///
+    /// # Args:
+    ///
+    /// `cards` - the cards
+    fn sorted_wire_ids(cards: &[Card]) -> Vec<u16> {
+
+        let mut wire_ids: Vec<u16> = cards.iter()
+            .map(Card::wire_id)
+            .collect();
+        wire_ids.sort();
+
+        wire_ids
+    }
+
+    /// Checks the given shuffle keeps every card of the shoe exactly once, and changes the order.
+    ///
+    /// # Args:
+    ///
+    /// `shuffle` - the shuffle to check
+    fn check_multiset_is_preserved(shuffle: &dyn Fn(&mut [Card], &mut dyn RngCore)) {
+
+        let original_cards = shoe_cards();
+        let mut cards = original_cards.clone();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        shuffle(&mut cards, &mut rng);
+
+        assert_eq!(cards.len(), original_cards.len());
+        assert_ne!(cards, original_cards);
+        assert_eq!(sorted_wire_ids(&cards), sorted_wire_ids(&original_cards));
+    }
+
+    #[test]
+    fn test_scripted_shuffle_deals_the_scripted_cards_first() {
+
+        let strategy = ScriptedShuffle {
+            cards: vec![
+                (Rank::Ace, Suit::Spades),
+                (Rank::King, Suit::Hearts),
+                (Rank::Five, Suit::Clubs),
+            ],
+        };
+
+        let mut cards: Vec<Card> = shoe_cards()
+            .into_iter()
+            .rev()
+            .collect();
+        let mut rng = StdRng::seed_from_u64(42);
+        strategy.shuffle(&mut cards, &mut rng);
+
+        let drawn_cards: Vec<(Rank, Suit)> = cards.iter()
+            .rev()
+            .take(3)
+            .map(|card| (card.rank, card.suit))
+            .collect();
+        assert_eq!(drawn_cards, strategy.cards);
+
+        let remaining_wire_ids: Vec<u16> = cards[..cards.len() - 3]
+            .iter()
+            .map(Card::wire_id)
+            .collect();
+        let mut sorted_remaining_wire_ids = remaining_wire_ids.clone();
+        sorted_remaining_wire_ids.sort();
+        assert_eq!(remaining_wire_ids, sorted_remaining_wire_ids);
+
+        assert_eq!(sorted_wire_ids(&cards), sorted_wire_ids(&shoe_cards()));
+    }
+
+    #[test]
+    fn test_scripted_shuffle_skips_the_missing_cards() {
+
+        let strategy = ScriptedShuffle {
+            cards: vec![
+                (Rank::Ace, Suit::Spades),
+                (Rank::Ace, Suit::Spades),
+                (Rank::Two, Suit::Diamonds),
+            ],
+        };
+
+        let mut cards: Vec<Card> = (0..ONE_DECK_CARDS_AMOUNT)
+            .filter_map(Card::from_wire_id)
+            .collect();
+        let mut rng = StdRng::seed_from_u64(42);
+        strategy.shuffle(&mut cards, &mut rng);
+
+        let last_card = cards[cards.len() - 1];
+        let second_last_card = cards[cards.len() - 2];
+        assert_eq!((last_card.rank, last_card.suit), (Rank::Ace, Suit::Spades));
+        assert_eq!((second_last_card.rank, second_last_card.suit), (Rank::Two, Suit::Diamonds));
+        assert_eq!(cards.len(), ONE_DECK_CARDS_AMOUNT as usize);
+    }
+
+    #[test]
+    fn test_riffle_preserves_the_cards() {
+        check_multiset_is_preserved(&riffle);
+    }
+
+    #[test]
+    fn test_strip_preserves_the_cards() {
+        check_multiset_is_preserved(&strip);
+    }
+
+    #[test]
+    fn test_box_cut_preserves_the_cards() {
+        check_multiset_is_preserved(&|cards, _| box_cut(cards));
+    }
+
+    #[test]
+    fn test_box_cut_of_a_few_cards() {
+
+        let mut cards: Vec<Card> = (0..3)
+            .filter_map(Card::from_wire_id)
+            .collect();
+        box_cut(&mut cards);
+
+        assert_eq!(sorted_wire_ids(&cards), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_strategies_preserve_the_cards() {
+        check_multiset_is_preserved(&|cards, rng| RandomShuffle.shuffle(cards, rng));
+        check_multiset_is_preserved(&|cards, rng| ContinuousShuffle.shuffle(cards, rng));
+        check_multiset_is_preserved(&|cards, rng| HandShuffle.shuffle(cards, rng));
+    }
+
+    #[test]
+    fn test_random_shuffle_does_not_depend_on_the_discards_order() {
+
+        let mut cards = shoe_cards();
+        let mut reversed_cards: Vec<Card> = shoe_cards()
+            .into_iter()
+            .rev()
+            .collect();
+
+        RandomShuffle.shuffle(&mut cards, &mut StdRng::seed_from_u64(42));
+        RandomShuffle.shuffle(&mut reversed_cards, &mut StdRng::seed_from_u64(42));
+
+        assert_eq!(cards, reversed_cards);
+    }
+}
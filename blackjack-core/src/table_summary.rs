@@ -0,0 +1,20 @@
+//! The summary of one table of the server lobby, sent to the clients so they can choose a table.
+
+use rules::TableRules;
+
+/// Contains the name, the rules and the amount of seated players of one table.
+/// The tables are identified by their position into the lobby tables list.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct TableSummary {
+    pub name: String,
+    pub rules: TableRules,
+    pub players_amount: u8,
+}
+
+impl TableSummary {
+
+    /// Indicates if all the seats of the table are taken.
+    pub fn is_full(&self) -> bool {
+        self.players_amount >= self.rules.seats_amount
+    }
+}
@@ -0,0 +1,18 @@
+//! The game actions a player can take, as listed by the server into every decision prompt.
+
+/// A game action, the server only accepts the actions it listed into the last prompt of the player.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    PlaceBet,
+    Hit,
+    Stand,
+    DoubleDown,
+    Split,
+    Surrender,
+    DeclineSurrender,
+    Insurance,
+    DeclineInsurance,
+    #[serde(other)]
+    Unknown,
+}
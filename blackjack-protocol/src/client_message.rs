@@ -0,0 +1,143 @@
+//! The messages sent by the client to the server.
+
+use blackjack_core::TableRules;
+
+use action::Action;
+use version::ProtocolVersion;
+
+/// All the messages the client can send. The lobby messages are accepted outside of any table,
+/// the game messages only from a seated player, when the current phase of his table allows them.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join {
+        name: String,
+        client_version: ProtocolVersion,
+    },
+    ListTables,
+    JoinTable {
+        table_index: u16,
+    },
+    WatchTable {
+        table_index: u16,
+    },
+    CreateTable {
+        name: String,
+        #[serde(default)]
+        rules: TableRules,
+    },
+    LeaveTable,
+    Resume {
+        session_token: String,
+    },
+    RequestSnapshot,
+    PlaceBet {
+        amount: u32,
+    },
+    Hit,
+    Stand,
+    DoubleDown,
+    Split,
+    Surrender,
+    DeclineSurrender,
+    Insurance {
+        stake: u32,
+    },
+    DeclineInsurance,
+    ClientSeed {
+        seed: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+impl ClientMessage {
+
+    /// Returns the game action of the message, if it is one.
+    pub fn action(&self) -> Option<Action> {
+        match self {
+            ClientMessage::PlaceBet { .. } => Some(Action::PlaceBet),
+            ClientMessage::Hit => Some(Action::Hit),
+            ClientMessage::Stand => Some(Action::Stand),
+            ClientMessage::DoubleDown => Some(Action::DoubleDown),
+            ClientMessage::Split => Some(Action::Split),
+            ClientMessage::Surrender => Some(Action::Surrender),
+            ClientMessage::DeclineSurrender => Some(Action::DeclineSurrender),
+            ClientMessage::Insurance { .. } => Some(Action::Insurance),
+            ClientMessage::DeclineInsurance => Some(Action::DeclineInsurance),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use serde_json;
+
+    #[test]
+    fn test_messages_are_tagged_by_their_snake_case_type() {
+        assert_eq!(
+            serde_json::to_string(&ClientMessage::JoinTable { table_index: 2 }).unwrap(),
+            r#"{"type":"join_table","table_index":2}"#,
+        );
+        assert_eq!(
+            serde_json::to_string(&ClientMessage::RequestSnapshot).unwrap(),
+            r#"{"type":"request_snapshot"}"#,
+        );
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+
+        let messages = vec![
+            ClientMessage::Join {
+                name: "Alice".to_string(),
+                client_version: ProtocolVersion { major: 3, minor: 0 },
+            },
+            ClientMessage::CreateTable {
+                name: "High stakes".to_string(),
+                rules: TableRules::default(),
+            },
+            ClientMessage::PlaceBet { amount: 10 },
+            ClientMessage::DoubleDown,
+            ClientMessage::Insurance { stake: 5 },
+            ClientMessage::ClientSeed { seed: "seed".to_string() },
+        ];
+
+        for message in messages {
+
+            let json = serde_json::to_string(&message).unwrap();
+            assert_eq!(serde_json::from_str::<ClientMessage>(&json).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_unknown_message_kind_is_decoded_as_unknown() {
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"order_drink"}"#).unwrap(),
+            ClientMessage::Unknown,
+        );
+    }
+
+    #[test]
+    fn test_create_table_without_rules_uses_the_default_rules() {
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"create_table","name":"Table"}"#).unwrap(),
+            ClientMessage::CreateTable {
+                name: "Table".to_string(),
+                rules: TableRules::default(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_action() {
+        assert_eq!(ClientMessage::Hit.action(), Some(Action::Hit));
+        assert_eq!(ClientMessage::Insurance { stake: 5 }.action(), Some(Action::Insurance));
+        assert_eq!(ClientMessage::ListTables.action(), None);
+        assert_eq!(ClientMessage::Unknown.action(), None);
+    }
+}
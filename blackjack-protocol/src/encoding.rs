@@ -0,0 +1,342 @@
+//! The encodings of the messages, negotiated through the WebSocket subprotocol when the client connects.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// The way the messages of a connection are encoded. The JSON messages are sent as text frames
+/// and remain the default, as they can be read while debugging, the MessagePack messages
+/// are sent as binary frames and are meant for the bots and the simulators.
+/// Both encodings carry the same tagged messages, the MessagePack maps keep the field names.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Encoding {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+
+    /// Returns the encoding of the given WebSocket subprotocol, if the subprotocol is a known one.
+    ///
+    /// # Args:
+    ///
+    /// `subprotocol` - the name of the subprotocol
+    pub fn from_subprotocol(subprotocol: &str) -> Option<Encoding> {
+        match subprotocol.trim() {
+            "blackjack.json" => Some(Encoding::Json),
+            "blackjack.msgpack" => Some(Encoding::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// Returns the encoding of the first known subprotocol among the ones offered by a client, if any.
+    /// The messages remain encoded in JSON when no offered subprotocol is known.
+    ///
+    /// # Args:
+    ///
+    /// `subprotocols` - the names of the subprotocols offered by the client, by order of preference
+    pub fn negotiate(subprotocols: &[&str]) -> Option<Encoding> {
+        subprotocols.iter()
+            .find_map(|subprotocol| Encoding::from_subprotocol(subprotocol))
+    }
+
+    /// Returns the name of the WebSocket subprotocol of the encoding.
+    pub fn subprotocol(self) -> &'static str {
+        match self {
+            Encoding::Json => "blackjack.json",
+            Encoding::MessagePack => "blackjack.msgpack",
+        }
+    }
+
+    /// Indicates if the messages are sent as binary frames rather than text frames.
+    pub fn is_binary(self) -> bool {
+        self == Encoding::MessagePack
+    }
+
+    /// Returns the encoded message, the JSON messages are valid UTF-8.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to encode
+    pub fn encode<T: Serialize>(
+        self,
+        message: &T,
+    ) -> Vec<u8> {
+        match self {
+            Encoding::Json => serde_json::to_vec(message).unwrap(),
+            Encoding::MessagePack => rmp_serde::to_vec_named(message).unwrap(),
+        }
+    }
+
+    /// Returns the decoded message, or the reason why it cannot be decoded.
+    ///
+    /// # Args:
+    ///
+    /// `data` - the encoded message
+    pub fn decode<T: DeserializeOwned>(
+        self,
+        data: &[u8],
+    ) -> Result<T, String> {
+        match self {
+            Encoding::Json => serde_json::from_slice(data).map_err(|error| error.to_string()),
+            Encoding::MessagePack => rmp_serde::from_slice(data).map_err(|error| error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use blackjack_core::{
+        HandResult,
+        TableRules,
+        TableSummary,
+    };
+
+    use action::Action;
+    use client_message::ClientMessage;
+    use error_code::ErrorCode;
+    use server_message::{
+        Offer,
+        ServerMessage,
+    };
+    use table_snapshot::{
+        SeatSnapshot,
+        TablePhase,
+        TableSnapshot,
+    };
+    use version::PROTOCOL_VERSION;
+
+    /// Returns one message of every kind the client can send.
+    fn client_messages() -> Vec<ClientMessage> {
+        vec![
+            ClientMessage::Join {
+                name: "Alice".to_string(),
+                client_version: PROTOCOL_VERSION,
+            },
+            ClientMessage::ListTables,
+            ClientMessage::JoinTable { table_index: 1 },
+            ClientMessage::WatchTable { table_index: 2 },
+            ClientMessage::CreateTable {
+                name: "Table".to_string(),
+                rules: TableRules::default(),
+            },
+            ClientMessage::LeaveTable,
+            ClientMessage::Resume { session_token: "token".to_string() },
+            ClientMessage::RequestSnapshot,
+            ClientMessage::PlaceBet { amount: 10 },
+            ClientMessage::Hit,
+            ClientMessage::Stand,
+            ClientMessage::DoubleDown,
+            ClientMessage::Split,
+            ClientMessage::Surrender,
+            ClientMessage::DeclineSurrender,
+            ClientMessage::Insurance { stake: 5 },
+            ClientMessage::DeclineInsurance,
+            ClientMessage::ClientSeed { seed: "seed".to_string() },
+            ClientMessage::Unknown,
+        ]
+    }
+
+    /// Returns one message of every kind the server can send.
+    fn server_messages() -> Vec<ServerMessage> {
+
+        let snapshot = TableSnapshot {
+            phase: TablePhase::Insurance,
+            active_seat: None,
+            seats: vec![
+                SeatSnapshot {
+                    seat_index: 0,
+                    name: "Alice".to_string(),
+                    bankroll: 90,
+                    bet: 10,
+                    hands: vec![vec![8, 51]],
+                    hands_points: vec![21],
+                    active_hand: 0,
+                    connected: false,
+                },
+            ],
+            bank_cards: vec![12],
+            bank_points: 11,
+            hole_card: true,
+            cards_amount: 309,
+        };
+
+        vec![
+            ServerMessage::Hello { version: PROTOCOL_VERSION },
+            ServerMessage::IncompatibleVersion { version: PROTOCOL_VERSION },
+            ServerMessage::Tables {
+                tables: vec![
+                    TableSummary {
+                        name: "Table".to_string(),
+                        rules: TableRules::default(),
+                        players_amount: 2,
+                    },
+                ],
+                error: Some("the table is full".to_string()),
+            },
+            ServerMessage::Welcome {
+                player_id: 1,
+                seat_index: 0,
+                rules: TableRules::default(),
+                bankroll: 100,
+                cards_amount: 311,
+            },
+            ServerMessage::Session { session_token: "token".to_string() },
+            ServerMessage::Spectating {
+                rules: TableRules::default(),
+                cards_amount: 311,
+            },
+            ServerMessage::Bankroll { amount: 90 },
+            ServerMessage::NewRound,
+            ServerMessage::PlayerCard {
+                seat_index: 0,
+                hand_index: 0,
+                card: 415,
+                points: 11,
+                cards_amount: 310,
+            },
+            ServerMessage::Split {
+                seat_index: 0,
+                hand_index: 1,
+            },
+            ServerMessage::ActiveHand {
+                seat_index: 0,
+                hand_index: 1,
+            },
+            ServerMessage::ActiveSeat { seat_index: 0 },
+            ServerMessage::BankCard {
+                card: 12,
+                points: 11,
+                cards_amount: 309,
+            },
+            ServerMessage::HoleCard { cards_amount: 308 },
+            ServerMessage::BankCards {
+                cards: vec![12, 50],
+                points: 21,
+            },
+            ServerMessage::Offer {
+                offer: Offer::Insurance,
+                amount: 5,
+            },
+            ServerMessage::InsuranceResult { amount: 15 },
+            ServerMessage::RoundResult {
+                seat_index: 0,
+                hand_index: 0,
+                result: HandResult::Push,
+                amount: 0,
+            },
+            ServerMessage::Shuffle { cards_amount: 312 },
+            ServerMessage::Countdown { seconds: 10 },
+            ServerMessage::Prompt {
+                actions: vec![Action::Insurance, Action::DeclineInsurance],
+            },
+            ServerMessage::TableSnapshot { snapshot },
+            ServerMessage::ShoeCommitment {
+                commitment: "hash".to_string(),
+                next_seed_commitment: "next hash".to_string(),
+            },
+            ServerMessage::ShoeReveal {
+                server_seed: "seed".to_string(),
+                cards: vec![415, 0, 51],
+                shoe_seed: u64::MAX,
+                seed_salt: "salt".to_string(),
+                client_seeds: vec!["alice".to_string()],
+                recomputable: true,
+            },
+            ServerMessage::Error {
+                code: ErrorCode::IllegalAction,
+                message: "this is not the turn of this seat".to_string(),
+            },
+            ServerMessage::Unknown,
+        ]
+    }
+
+    #[test]
+    fn test_client_messages_round_trip() {
+
+        for encoding in [Encoding::Json, Encoding::MessagePack] {
+
+            for message in client_messages() {
+
+                let data = encoding.encode(&message);
+                assert_eq!(encoding.decode::<ClientMessage>(&data), Ok(message));
+            }
+        }
+    }
+
+    #[test]
+    fn test_server_messages_round_trip() {
+
+        for encoding in [Encoding::Json, Encoding::MessagePack] {
+
+            for message in server_messages() {
+
+                let data = encoding.encode(&message);
+                assert_eq!(encoding.decode::<ServerMessage>(&data), Ok(message));
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_pack_messages_keep_the_field_names() {
+
+        let message = ServerMessage::Bankroll { amount: 90 };
+
+        assert_eq!(
+            Encoding::MessagePack.encode(&message),
+            rmp_serde::to_vec_named(&message).unwrap(),
+        );
+        assert_eq!(
+            rmp_serde::from_slice::<ServerMessage>(&rmp_serde::to_vec_named(&message).unwrap()).unwrap(),
+            message,
+        );
+    }
+
+    #[test]
+    fn test_json_messages_are_text() {
+        for message in server_messages() {
+            assert!(String::from_utf8(Encoding::Json.encode(&message)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_malformed_messages_are_not_decoded() {
+        assert!(Encoding::Json.decode::<ClientMessage>(&Encoding::MessagePack.encode(&ClientMessage::Hit)).is_err());
+        assert!(Encoding::MessagePack.decode::<ClientMessage>(b"{\"type\":").is_err());
+    }
+
+    #[test]
+    fn test_subprotocols() {
+
+        for encoding in [Encoding::Json, Encoding::MessagePack] {
+            assert_eq!(Encoding::from_subprotocol(encoding.subprotocol()), Some(encoding));
+        }
+
+        assert_eq!(Encoding::from_subprotocol(" blackjack.msgpack "), Some(Encoding::MessagePack));
+        assert_eq!(Encoding::from_subprotocol("blackjack.xml"), None);
+        assert!(Encoding::MessagePack.is_binary());
+        assert!(!Encoding::Json.is_binary());
+    }
+
+    #[test]
+    fn test_negotiation_selects_the_first_known_subprotocol() {
+        assert_eq!(
+            Encoding::negotiate(&["chat", "blackjack.msgpack", "blackjack.json"]),
+            Some(Encoding::MessagePack),
+        );
+        assert_eq!(
+            Encoding::negotiate(&["blackjack.json", "blackjack.msgpack"]),
+            Some(Encoding::Json),
+        );
+    }
+
+    #[test]
+    fn test_negotiation_falls_back_to_json() {
+        assert_eq!(Encoding::negotiate(&[]), None);
+        assert_eq!(Encoding::negotiate(&["chat", "blackjack.xml"]), None);
+        assert_eq!(Encoding::negotiate(&["blackjack.xml"]).unwrap_or_default(), Encoding::Json);
+    }
+}
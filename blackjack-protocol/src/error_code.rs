@@ -0,0 +1,22 @@
+//! The codes of the errors sent back by the server when it rejects a message.
+
+/// The reason why a message of the client has been rejected.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The message does not use the encoding negotiated for the connection, or cannot be decoded.
+    MalformedMessage,
+    /// The type of the message is unknown to the server.
+    UnknownMessage,
+    /// The name of the player is empty, too long, or contains control characters.
+    InvalidName,
+    /// The message is not allowed in the current phase, out of turn, or by the table rules.
+    IllegalAction,
+    /// The bet is out of the table limits.
+    InvalidBet,
+    /// The bankroll of the player is not enough to cover the stake.
+    InsufficientFunds,
+    /// An error code added by a newer minor version.
+    #[serde(other)]
+    Unknown,
+}
@@ -0,0 +1,52 @@
+//! The messages exchanged between the client and the server, shared by both of them.
+//!
+//! Every message is a JSON object tagged by its `type`, carrying only the fields of its kind.
+//! The client sends the name of the player and its protocol version with a `join` message right after connecting,
+//! the server answers with its own version, or with an `incompatible_version` message
+//! before closing the connection if both versions are not compatible. The player is welcomed
+//! with his identifier, his seat, the table rules and his bankroll when he sits down at a table.
+//! The complete public state of the table is sent with a `table_snapshot` message when a player sits down,
+//! resumes his session or starts watching the table, and whenever the client requests it.
+//!
+//! The messages are encoded in JSON text frames by default. A client can ask for MessagePack binary frames
+//! by offering the `blackjack.msgpack` WebSocket subprotocol during the handshake, the server accepts it
+//! and both peers use that encoding for the whole connection.
+//!
+//! The compatibility rules are the following:
+//!
+//! * the major version changes when a message kind or a field is removed, renamed or changes meaning,
+//!   the peers with different major versions cannot talk to each other,
+//! * the minor version changes when a message kind or an optional field (with a default value) is added:
+//!   a peer ignores the unknown message kinds, decoded as `Unknown`, and the unknown fields.
+
+extern crate serde;
+#[macro_use] extern crate serde_derive;
+extern crate serde_json;
+extern crate rmp_serde;
+extern crate blackjack_core;
+
+mod action;
+mod client_message;
+mod encoding;
+mod error_code;
+mod server_message;
+mod table_snapshot;
+mod version;
+
+pub use action::Action;
+pub use client_message::ClientMessage;
+pub use encoding::Encoding;
+pub use error_code::ErrorCode;
+pub use server_message::{
+    Offer,
+    ServerMessage,
+};
+pub use table_snapshot::{
+    SeatSnapshot,
+    TablePhase,
+    TableSnapshot,
+};
+pub use version::{
+    PROTOCOL_VERSION,
+    ProtocolVersion,
+};
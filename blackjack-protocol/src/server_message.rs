@@ -0,0 +1,247 @@
+//! The messages sent by the server to the client.
+
+use blackjack_core::{
+    HandResult,
+    TableRules,
+    TableSummary,
+};
+
+use action::Action;
+use error_code::ErrorCode;
+use table_snapshot::TableSnapshot;
+use version::ProtocolVersion;
+
+/// The decisions offered to a player before the hands are played.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Offer {
+    Insurance,
+    EvenMoney,
+    Surrender,
+}
+
+/// All the messages the server can send. The cards are identified by their wire id,
+/// which tells apart the copies of a card coming from different decks,
+/// the messages about the cards carry the amount of cards remaining into the shoe right after the draw.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Hello {
+        version: ProtocolVersion,
+    },
+    IncompatibleVersion {
+        version: ProtocolVersion,
+    },
+    Tables {
+        tables: Vec<TableSummary>,
+        error: Option<String>,
+    },
+    Welcome {
+        player_id: u32,
+        seat_index: u8,
+        rules: TableRules,
+        bankroll: u32,
+        cards_amount: u16,
+    },
+    Session {
+        session_token: String,
+    },
+    Spectating {
+        rules: TableRules,
+        cards_amount: u16,
+    },
+    Bankroll {
+        amount: u32,
+    },
+    NewRound,
+    PlayerCard {
+        seat_index: u8,
+        hand_index: u8,
+        card: u16,
+        points: u8,
+        cards_amount: u16,
+    },
+    Split {
+        seat_index: u8,
+        hand_index: u8,
+    },
+    ActiveHand {
+        seat_index: u8,
+        hand_index: u8,
+    },
+    ActiveSeat {
+        seat_index: u8,
+    },
+    BankCard {
+        card: u16,
+        points: u8,
+        cards_amount: u16,
+    },
+    HoleCard {
+        cards_amount: u16,
+    },
+    BankCards {
+        cards: Vec<u16>,
+        points: u8,
+    },
+    Offer {
+        offer: Offer,
+        amount: u32,
+    },
+    InsuranceResult {
+        amount: u32,
+    },
+    RoundResult {
+        seat_index: u8,
+        hand_index: u8,
+        result: HandResult,
+        amount: u32,
+    },
+    Shuffle {
+        cards_amount: u16,
+    },
+    Countdown {
+        seconds: u16,
+    },
+    Prompt {
+        actions: Vec<Action>,
+    },
+    TableSnapshot {
+        snapshot: TableSnapshot,
+    },
+    ShoeCommitment {
+        commitment: String,
+        #[serde(default)]
+        next_seed_commitment: String,
+    },
+    ShoeReveal {
+        server_seed: String,
+        cards: Vec<u16>,
+        #[serde(default)]
+        shoe_seed: u64,
+        #[serde(default)]
+        seed_salt: String,
+        #[serde(default)]
+        client_seeds: Vec<String>,
+        #[serde(default)]
+        recomputable: bool,
+    },
+    Error {
+        code: ErrorCode,
+        message: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use serde_json;
+
+    #[test]
+    fn test_messages_are_tagged_by_their_snake_case_type() {
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::HoleCard { cards_amount: 300 }).unwrap(),
+            r#"{"type":"hole_card","cards_amount":300}"#,
+        );
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Offer { offer: Offer::EvenMoney, amount: 10 }).unwrap(),
+            r#"{"type":"offer","offer":"even_money","amount":10}"#,
+        );
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+
+        let messages = vec![
+            ServerMessage::Hello {
+                version: ProtocolVersion { major: 3, minor: 0 },
+            },
+            ServerMessage::RoundResult {
+                seat_index: 1,
+                hand_index: 0,
+                result: HandResult::Blackjack,
+                amount: 15,
+            },
+            ServerMessage::Prompt {
+                actions: vec![Action::Hit, Action::Stand],
+            },
+            ServerMessage::ShoeReveal {
+                server_seed: "seed".to_string(),
+                cards: vec![415, 0, 51],
+                shoe_seed: 42,
+                seed_salt: "salt".to_string(),
+                client_seeds: vec!["alice".to_string()],
+                recomputable: true,
+            },
+            ServerMessage::Error {
+                code: ErrorCode::InvalidBet,
+                message: "the bet is too high".to_string(),
+            },
+        ];
+
+        for message in messages {
+
+            let json = serde_json::to_string(&message).unwrap();
+            assert_eq!(serde_json::from_str::<ServerMessage>(&json).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_unknown_message_kind_is_decoded_as_unknown() {
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(r#"{"type":"serve_drink","drink":"water"}"#).unwrap(),
+            ServerMessage::Unknown,
+        );
+    }
+
+    #[test]
+    fn test_unknown_values_are_decoded_as_unknown() {
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(r#"{"type":"error","code":"table_closed","message":""}"#).unwrap(),
+            ServerMessage::Error {
+                code: ErrorCode::Unknown,
+                message: String::new(),
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(r#"{"type":"prompt","actions":["hit","insure_side_bet"]}"#).unwrap(),
+            ServerMessage::Prompt {
+                actions: vec![Action::Hit, Action::Unknown],
+            },
+        );
+    }
+
+    #[test]
+    fn test_shoe_messages_of_older_minor_versions_use_the_default_fields() {
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(r#"{"type":"shoe_commitment","commitment":"hash"}"#).unwrap(),
+            ServerMessage::ShoeCommitment {
+                commitment: "hash".to_string(),
+                next_seed_commitment: String::new(),
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(r#"{"type":"shoe_reveal","server_seed":"seed","cards":[1,2]}"#).unwrap(),
+            ServerMessage::ShoeReveal {
+                server_seed: "seed".to_string(),
+                cards: vec![1, 2],
+                shoe_seed: 0,
+                seed_salt: String::new(),
+                client_seeds: vec![],
+                recomputable: false,
+            },
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_are_ignored() {
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(r#"{"type":"bankroll","amount":100,"currency":"chips"}"#).unwrap(),
+            ServerMessage::Bankroll { amount: 100 },
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! The complete public state of a table, sent at once so a client never depends on the previous messages.
+
+/// The phase of the round of a table, as seen by the players and the spectators.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TablePhase {
+    Betting,
+    Dealing,
+    EarlySurrender,
+    Insurance,
+    PlayerTurn,
+    DealerTurn,
+    Settlement,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The public state of one occupied seat: the player, his money and the cards of his hands.
+/// The hands are the ones of the current round, or of the last round between two rounds.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct SeatSnapshot {
+    pub seat_index: u8,
+    pub name: String,
+    pub bankroll: u32,
+    pub bet: u32,
+    pub hands: Vec<Vec<u16>>,
+    pub hands_points: Vec<u8>,
+    pub active_hand: u8,
+    pub connected: bool,
+}
+
+/// The public state of a table. During a round, the bank cards only contain the up card
+/// and the hole card is only counted, all the bank cards are revealed between two rounds.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct TableSnapshot {
+    pub phase: TablePhase,
+    pub active_seat: Option<u8>,
+    pub seats: Vec<SeatSnapshot>,
+    pub bank_cards: Vec<u16>,
+    pub bank_points: u8,
+    pub hole_card: bool,
+    pub cards_amount: u16,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use serde_json;
+
+    #[test]
+    fn test_snapshot_round_trips() {
+
+        let snapshot = TableSnapshot {
+            phase: TablePhase::PlayerTurn,
+            active_seat: Some(0),
+            seats: vec![
+                SeatSnapshot {
+                    seat_index: 0,
+                    name: "Alice".to_string(),
+                    bankroll: 90,
+                    bet: 10,
+                    hands: vec![vec![8, 51]],
+                    hands_points: vec![21],
+                    active_hand: 0,
+                    connected: true,
+                },
+            ],
+            bank_cards: vec![12],
+            bank_points: 11,
+            hole_card: true,
+            cards_amount: 309,
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<TableSnapshot>(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_phases_are_snake_case() {
+        assert_eq!(serde_json::to_string(&TablePhase::EarlySurrender).unwrap(), r#""early_surrender""#);
+        assert_eq!(serde_json::from_str::<TablePhase>(r#""dealer_turn""#).unwrap(), TablePhase::DealerTurn);
+    }
+
+    #[test]
+    fn test_unknown_phase_is_decoded_as_unknown() {
+        assert_eq!(serde_json::from_str::<TablePhase>(r#""side_bets""#).unwrap(), TablePhase::Unknown);
+    }
+}
@@ -0,0 +1,70 @@
+//! The version of the protocol, negotiated when the client connects.
+
+use std::fmt;
+
+/// The version of the protocol spoken by this build of the client and of the server.
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion {
+    major: 3,
+    minor: 0,
+};
+
+/// A protocol version, two peers can talk to each other as long as they share the same major version.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+
+    /// Indicates if a peer speaking this version can talk to a peer speaking the given one.
+    ///
+    /// # Args:
+    ///
+    /// `other` - the version of the other peer
+    pub fn is_compatible_with(
+        &self,
+        other: &ProtocolVersion,
+    ) -> bool {
+        self.major == other.major
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+
+    fn fmt(
+        &self,
+        formatter: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        write!(formatter, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_same_major_versions_are_compatible() {
+
+        let version = ProtocolVersion { major: 3, minor: 0 };
+
+        assert!(version.is_compatible_with(&ProtocolVersion { major: 3, minor: 0 }));
+        assert!(version.is_compatible_with(&ProtocolVersion { major: 3, minor: 7 }));
+    }
+
+    #[test]
+    fn test_different_major_versions_are_incompatible() {
+
+        let version = ProtocolVersion { major: 3, minor: 0 };
+
+        assert!(!version.is_compatible_with(&ProtocolVersion { major: 2, minor: 4 }));
+        assert!(!version.is_compatible_with(&ProtocolVersion { major: 4, minor: 0 }));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(ProtocolVersion { major: 3, minor: 12 }.to_string(), "3.12");
+    }
+}
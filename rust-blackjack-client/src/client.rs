@@ -5,9 +5,11 @@ use ws::{
     Result,
     Handshake,
     Message,
+    Request,
     Sender,
     CloseCode,
 };
+use url::Url;
 
 use std::sync::{
     Mutex,
@@ -15,49 +17,105 @@ use std::sync::{
     Arc,
     mpsc,
 };
+use std::time::{
+    Duration,
+    Instant,
+    SystemTime,
+    UNIX_EPOCH,
+};
 
-use crate::socket_message::SocketMessage;
-use crate::message_action::MessageAction;
+use crate::player_action::PlayerAction;
 use crate::event::Event;
+use crate::output::Output;
+use crate::shoe_verification::ShoeVerification;
+
+use blackjack_core::{
+    Card,
+    Hand,
+    HandResult,
+    SurrenderRule,
+    TableRules,
+    TableSummary,
+};
+use blackjack_protocol::{
+    Action,
+    ClientMessage,
+    Encoding,
+    ErrorCode,
+    Offer,
+    ServerMessage,
+    TableSnapshot,
+    PROTOCOL_VERSION,
+};
+
+/// The results of the hands of one seat, with the amount won or lost by every hand.
+pub type SeatResults = Vec<(HandResult, u32)>;
 
 pub struct Client {
-    pub player_cards_mutex_arc: Arc<Mutex<Vec<u16>>>,
+    pub player_cards_mutex_arc: Arc<Mutex<Vec<Vec<u16>>>>,
     pub bank_cards_mutex_arc: Arc<Mutex<Vec<u16>>>,
-    pub player_points_mutex_arc: Arc<Mutex<u8>>,
+    pub player_points_mutex_arc: Arc<Mutex<Vec<u8>>>,
+    pub active_hand_mutex_arc: Arc<Mutex<usize>>,
     pub bank_points_mutex_arc: Arc<Mutex<u8>>,
     pub cards_amount_arc: Arc<Mutex<u16>>,
     pub displayed_bank_cards_amount_mutex_arc: Arc<Mutex<usize>>,
     pub socket_sender: Sender,
+    pub encoding: Encoding,
     pub channel_sender: mpsc::Sender<Event>,
-    pub basic_strategy_action_mutex_arc: Arc<Mutex<MessageAction>>,
+    pub basic_strategy_action_mutex_arc: Arc<Mutex<PlayerAction>>,
+    pub rules_mutex_arc: Arc<Mutex<TableRules>>,
+    pub offer_mutex_arc: Arc<Mutex<Option<(Offer, u32)>>>,
+    pub insurance_result_mutex_arc: Arc<Mutex<Option<u32>>>,
+    pub round_results_mutex_arc: Arc<Mutex<Vec<(HandResult, u32)>>>,
+    pub bankroll_mutex_arc: Arc<Mutex<u32>>,
+    pub seats_cards_mutex_arc: Arc<Mutex<Vec<Vec<Vec<u16>>>>>,
+    pub seats_results_mutex_arc: Arc<Mutex<Vec<SeatResults>>>,
+    pub playing_seat_mutex_arc: Arc<Mutex<Option<u8>>>,
+    pub lobby_tables_mutex_arc: Arc<Mutex<Option<Vec<TableSummary>>>>,
+    pub lobby_message_mutex_arc: Arc<Mutex<String>>,
+    pub session_token_mutex_arc: Arc<Mutex<Option<String>>>,
+    pub countdown_mutex_arc: Arc<Mutex<Option<Instant>>>,
+    pub error_mutex_arc: Arc<Mutex<Option<(String, Instant)>>>,
+    pub legal_actions_mutex_arc: Arc<Mutex<Vec<Action>>>,
+    pub player_name: String,
+    pub seat_index: Option<u8>,
+    pub resuming: bool,
+    pub shoe_verification: ShoeVerification,
 }
 
-/// Returns the a card points amount according to its index.
-fn get_card_points(card_index: u16) -> u8 {
-
-    const ONE_SET_CARDS_AMOUNT: u16 = 52;
-    let card_index = ( card_index % ONE_SET_CARDS_AMOUNT ) as u8;
-
-    const TEN_POINTS_CARDS_START_INDEX: u8 = 32;
-    const ACE_CARDS_START_INDEX: u8 = 48;
-
-    if card_index >= TEN_POINTS_CARDS_START_INDEX &&
-        card_index < ACE_CARDS_START_INDEX {
-
-        const TEN_VALUE_CARDS_POINTS_AMOUNT: u8 = 10;
-        return TEN_VALUE_CARDS_POINTS_AMOUNT;
-    }
-    else if card_index >= ACE_CARDS_START_INDEX {
-
-        const ACE_CARDS_POINTS_AMOUNT: u8 = 11;
-        return ACE_CARDS_POINTS_AMOUNT;
+/// Returns the a card points amount according to its wire id, 0 for an unknown card.
+fn get_card_points(card_id: u16) -> u8 {
+    Card::from_wire_id(card_id)
+        .map_or(0, |card| card.points())
+}
 
-    }
+/// Returns the hand made of the given cards.
+///
+/// # Args:
+///
+/// `cards` - the wire ids of the cards of the hand
+/// `from_split` - true if the hand comes from a split
+pub fn get_hand(
+    cards: &[u16],
+    from_split: bool,
+) -> Hand {
+    Hand::from_cards(
+        cards.iter()
+            .filter_map(|card_id| Card::from_wire_id(*card_id))
+            .collect(),
+        from_split,
+    )
+}
 
-    const CARDS_WITH_SAME_VALUE_BY_COLOR: u8 = 4;
-    const MINIMUM_CARD_VALUE: u8 = 2;
-    return card_index / CARDS_WITH_SAME_VALUE_BY_COLOR
-        + MINIMUM_CARD_VALUE;
+/// Returns the points of every given hand, all the hands come from a split if there are more than one.
+///
+/// # Args:
+///
+/// `hands` - the wire ids of the cards of every hand
+pub fn get_hands_points(hands: &[Vec<u16>]) -> Vec<u8> {
+    hands.iter()
+        .map(|cards| get_hand(cards, hands.len() > 1).soft_total())
+        .collect()
 }
 
 /// Indicates the action to follow according to basic strategy rules
@@ -66,16 +124,47 @@ fn get_card_points(card_index: u16) -> u8 {
 ///
 /// `player_cards` - the current player cards list
 /// `bank_cards` - the current bank cards list
+/// `surrender` - the surrender currently available to the player, forbidden if none
 fn get_strategic_action(
     player_cards: &Vec<u16>,
     bank_cards: &Vec<u16>,
-) -> MessageAction {
+    surrender: SurrenderRule,
+) -> PlayerAction {
 
     let first_player_card = get_card_points(*player_cards.get(0).unwrap());
     let second_player_card = get_card_points(*player_cards.get(1).unwrap());
     let player_points = first_player_card + second_player_card;
     let bank_card = get_card_points(*bank_cards.get(0).unwrap());
 
+    /* only hard hands are surrendered, and a pair of eights
+       is split rather than surrendered except against an ace before the peek */
+    let hard_hand = first_player_card != 11 && second_player_card != 11;
+    let eights = first_player_card == 8 && second_player_card == 8;
+
+    let surrender_is_correct = hard_hand && match surrender {
+        SurrenderRule::Forbidden => false,
+        SurrenderRule::Late => {
+            !eights && (
+                player_points == 16 && bank_card >= 9 ||
+                player_points == 15 && bank_card == 10
+            )
+        },
+        SurrenderRule::Early => {
+            bank_card == 11 && (
+                (5..=7).contains(&player_points) ||
+                (12..=17).contains(&player_points)
+            ) ||
+            !eights && (
+                bank_card == 10 && (14..=16).contains(&player_points) ||
+                bank_card == 9 && player_points == 16
+            )
+        },
+    };
+
+    if surrender_is_correct {
+        return PlayerAction::Surrender;
+    }
+
     /* the player got a pair */
     if first_player_card == second_player_card {
 
@@ -97,7 +186,7 @@ fn get_strategic_action(
                 bank_card >= 4
             )
         {
-            return MessageAction::Split;
+            return PlayerAction::Split;
         }
 
         if
@@ -111,7 +200,7 @@ fn get_strategic_action(
                 first_player_card == 2
             ) && bank_card < 4
         {
-            return MessageAction::DoubleDown;
+            return PlayerAction::DoubleDown;
         }
     }
 
@@ -129,7 +218,7 @@ fn get_strategic_action(
             bank_card <= 6
         )
     {
-        return MessageAction::Stand;
+        return PlayerAction::Stand;
     }
     else if
         (
@@ -152,7 +241,7 @@ fn get_strategic_action(
             )
         )
     {
-        return MessageAction::Hit;
+        return PlayerAction::Hit;
     }
     else if
         player_points == 11 ||
@@ -163,34 +252,383 @@ fn get_strategic_action(
             bank_card <= 6
         )
     {
-        return MessageAction::DoubleDown;
+        return PlayerAction::DoubleDown;
+    }
+
+    return PlayerAction::Stand;
+}
+
+impl Client {
+
+    /// Updates the basic strategy action according to the active player hand and the bank card.
+    /// Nothing is updated until the active hand got its two first cards.
+    /// The early surrender chart applies while the early surrender is offered,
+    /// the late surrender one applies to the first decision afterwards.
+    fn update_basic_strategy_action(&self) {
+
+        const REQUIRED_CARDS_AMOUNT_FOR_STRATEGY: usize = 2;
+
+        let player_hands: MutexGuard<Vec<Vec<u16>>> =
+            self.player_cards_mutex_arc.lock().unwrap();
+        let active_hand: MutexGuard<usize> =
+            self.active_hand_mutex_arc.lock().unwrap();
+        let bank_cards: MutexGuard<Vec<u16>> =
+            self.bank_cards_mutex_arc.lock().unwrap();
+
+        let player_cards = match player_hands.get(*active_hand) {
+            Some(cards) => cards,
+            None => return,
+        };
+
+        if player_cards.len() < REQUIRED_CARDS_AMOUNT_FOR_STRATEGY ||
+            bank_cards.is_empty() {
+            return;
+        }
+
+        let offer: MutexGuard<Option<(Offer, u32)>> =
+            self.offer_mutex_arc.lock().unwrap();
+        let legal_actions: MutexGuard<Vec<Action>> =
+            self.legal_actions_mutex_arc.lock().unwrap();
+
+        let surrender = match *offer {
+            Some((Offer::Surrender, _)) => SurrenderRule::Early,
+            _ if legal_actions.contains(&Action::Surrender) => SurrenderRule::Late,
+            _ => SurrenderRule::Forbidden,
+        };
+
+        let mut basic_strategy_action: MutexGuard<PlayerAction> =
+            self.basic_strategy_action_mutex_arc.lock().unwrap();
+        *basic_strategy_action = get_strategic_action(
+            player_cards,
+            &bank_cards,
+            surrender,
+        );
+    }
+
+    /// Forgets everything about the left table, in order to display the lobby
+    /// and start from scratch at the next joined table.
+    fn clear_table(&self) {
+
+        self.player_cards_mutex_arc.lock().unwrap().clear();
+        self.player_points_mutex_arc.lock().unwrap().clear();
+        *self.active_hand_mutex_arc.lock().unwrap() = 0;
+        self.bank_cards_mutex_arc.lock().unwrap().clear();
+        *self.bank_points_mutex_arc.lock().unwrap() = 0;
+
+        const DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT: usize = 1;
+        *self.displayed_bank_cards_amount_mutex_arc.lock().unwrap() =
+            DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT;
+
+        *self.offer_mutex_arc.lock().unwrap() = None;
+        *self.insurance_result_mutex_arc.lock().unwrap() = None;
+        self.round_results_mutex_arc.lock().unwrap().clear();
+        *self.bankroll_mutex_arc.lock().unwrap() = 0;
+        self.seats_cards_mutex_arc.lock().unwrap().clear();
+        self.seats_results_mutex_arc.lock().unwrap().clear();
+        *self.playing_seat_mutex_arc.lock().unwrap() = None;
+        *self.countdown_mutex_arc.lock().unwrap() = None;
+        self.legal_actions_mutex_arc.lock().unwrap().clear();
+    }
+
+    /// Replaces the whole state of the table with the received snapshot. The hands of the player are locked first,
+    /// as the main thread does during every frame, so the table is never drawn with a half applied snapshot.
+    ///
+    /// # Args:
+    ///
+    /// `snapshot` - the complete public state of the table
+    fn apply_snapshot(
+        &self,
+        snapshot: TableSnapshot,
+    ) {
+
+        let mut player_hands: MutexGuard<Vec<Vec<u16>>> =
+            self.player_cards_mutex_arc.lock().unwrap();
+        let mut player_points: MutexGuard<Vec<u8>> =
+            self.player_points_mutex_arc.lock().unwrap();
+        let mut active_hand: MutexGuard<usize> =
+            self.active_hand_mutex_arc.lock().unwrap();
+        let mut bank_cards: MutexGuard<Vec<u16>> =
+            self.bank_cards_mutex_arc.lock().unwrap();
+        let mut bank_points: MutexGuard<u8> =
+            self.bank_points_mutex_arc.lock().unwrap();
+        let mut displayed_bank_cards_amount: MutexGuard<usize> =
+            self.displayed_bank_cards_amount_mutex_arc.lock().unwrap();
+        let mut seats_cards: MutexGuard<Vec<Vec<Vec<u16>>>> =
+            self.seats_cards_mutex_arc.lock().unwrap();
+
+        player_hands.clear();
+        player_points.clear();
+        *active_hand = 0;
+        seats_cards.clear();
+
+        for seat in snapshot.seats {
+
+            if Some(seat.seat_index) == self.seat_index {
+
+                *player_hands = seat.hands;
+                *player_points = seat.hands_points;
+                *active_hand = seat.active_hand as usize;
+                *self.bankroll_mutex_arc.lock().unwrap() = seat.bankroll;
+                continue;
+            }
+
+            let seat_index = seat.seat_index as usize;
+
+            if seats_cards.len() <= seat_index {
+                seats_cards.resize(seat_index + 1, vec![]);
+            }
+            seats_cards[seat_index] = seat.hands;
+        }
+
+        /* the revealed bank cards are displayed at once, without drawing them one by one again */
+        const DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT: usize = 1;
+        *displayed_bank_cards_amount = if snapshot.hole_card {
+            DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT
+        } else {
+            snapshot.bank_cards.len().max(DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT)
+        };
+
+        *bank_cards = snapshot.bank_cards;
+        *bank_points = snapshot.bank_points;
+
+        *self.cards_amount_arc.lock().unwrap() = snapshot.cards_amount;
+        *self.playing_seat_mutex_arc.lock().unwrap() = snapshot.active_seat
+            .filter(|seat_index| Some(*seat_index) != self.seat_index);
+    }
+
+    /// Keeps the given error with the time it happened, in order to display it for a while.
+    ///
+    /// # Args:
+    ///
+    /// `error` - the description of the error
+    fn show_error(
+        &self,
+        error: String,
+    ) {
+
+        println!("Error: {}.", error);
+
+        *self.error_mutex_arc.lock().unwrap() = Some((error, Instant::now()));
+    }
+
+    /// Returns the output of the connection, sending the messages with the negotiated encoding.
+    fn output(&self) -> Output {
+        Output::new(
+            self.socket_sender.clone(),
+            self.encoding,
+        )
+    }
+
+    /// Sends the given message to the server.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    fn send(
+        &self,
+        message: &ClientMessage,
+    ) {
+        self.output().send(message);
     }
 
-    return MessageAction::Stand;
+    /// Records the dealt cards of the current shoe and verifies them once the order of the shoe is revealed.
+    /// A new seed is sent to the server for every new shoe, it is mixed into the shuffle of the next one.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the received message
+    fn verify_shoe(
+        &mut self,
+        message: &ServerMessage,
+    ) {
+
+        match message {
+            ServerMessage::PlayerCard { card, cards_amount, .. } |
+            ServerMessage::BankCard { card, cards_amount, .. } => {
+                self.shoe_verification.record_card(*cards_amount, *card);
+            },
+            ServerMessage::HoleCard { cards_amount } => {
+                self.shoe_verification.record_hole_card(*cards_amount);
+            },
+            ServerMessage::BankCards { cards, .. } => {
+                self.shoe_verification.reveal_hole_card(cards);
+            },
+            ServerMessage::ShoeReveal { server_seed, cards, shoe_seed, seed_salt, client_seeds, recomputable } => {
+
+                let verification = self.shoe_verification.verify(
+                    server_seed,
+                    cards,
+                    *shoe_seed,
+                    seed_salt,
+                    client_seeds,
+                    *recomputable,
+                );
+
+                match verification {
+                    Ok(cards_amount) if *recomputable => println!(
+                        "Shoe verified, {} cards dealt in the committed order, shuffled from the revealed seeds.",
+                        cards_amount,
+                    ),
+                    Ok(cards_amount) => println!(
+                        "Shoe verified, {} cards dealt in the committed order, the shuffle of the table cannot be recomputed.",
+                        cards_amount,
+                    ),
+                    Err(error) => println!("Shoe verification failed: {}.", error),
+                }
+            },
+            ServerMessage::ShoeCommitment { commitment, next_seed_commitment } => {
+
+                self.shoe_verification.commit(
+                    commitment.clone(),
+                    next_seed_commitment.clone(),
+                );
+
+                /* the seed only has to be unknown to the server before it is sent,
+                   the spectators cannot send any seed */
+                if self.seat_index.is_none() {
+                    return;
+                }
+
+                let client_seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos();
+                let client_seed = format!("{:x}", client_seed);
+
+                self.shoe_verification.record_client_seed(client_seed.clone());
+                self.send(&ClientMessage::ClientSeed {
+                    seed: client_seed,
+                });
+            },
+            _ => {},
+        }
+    }
+
+    /// Updates the cards of the hands of another player of the table according to the received message.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message about the hands of another player
+    fn update_other_seat(
+        &self,
+        message: &ServerMessage,
+    ) {
+
+        let (seat_index, hand_index) = match *message {
+            ServerMessage::PlayerCard { seat_index, hand_index, .. } |
+            ServerMessage::Split { seat_index, hand_index } |
+            ServerMessage::ActiveHand { seat_index, hand_index } => (seat_index as usize, hand_index as usize),
+            _ => return,
+        };
+
+        let mut seats_cards: MutexGuard<Vec<Vec<Vec<u16>>>> =
+            self.seats_cards_mutex_arc.lock().unwrap();
+
+        if seats_cards.len() <= seat_index {
+            seats_cards.resize(seat_index + 1, vec![]);
+        }
+
+        let hands = &mut seats_cards[seat_index];
+
+        match *message {
+            ServerMessage::PlayerCard { card, cards_amount, .. } => {
+
+                if hands.len() <= hand_index {
+                    hands.resize(hand_index + 1, vec![]);
+                }
+                hands[hand_index].push(card);
+
+                *self.cards_amount_arc.lock().unwrap() = cards_amount;
+            },
+            ServerMessage::Split { .. } => {
+
+                let split_card = match hands.get_mut(hand_index).and_then(Vec::pop) {
+                    Some(split_card) => split_card,
+                    None => {
+                        self.show_error("the split hand of another player is unknown".to_string());
+                        return;
+                    },
+                };
+
+                hands.insert(
+                    hand_index + 1,
+                    vec![split_card],
+                );
+            },
+            _ => {},
+        }
+    }
 }
 
 impl Handler for Client {
 
+    /// Called before connecting, offers the WebSocket subprotocol of the requested encoding to the server.
+    ///
+    /// # Args:
+    ///
+    /// `url` - the address of the server
+    fn build_request(
+        &mut self,
+        url: &Url,
+    ) -> Result<Request> {
+
+        let mut request = Request::from_url(url)?;
+        request.add_protocol(self.encoding.subprotocol());
+
+        Ok(request)
+    }
+
     /// Called when a successful connexion has been established with the server,
     /// sends a successful connection event to the main thread through the channel;
     /// sending that first message to the main thread unlocks it and starts rendering the window.
+    /// The messages are encoded the way the server accepted, in JSON if it accepted no subprotocol.
+    /// The player joins the server with his name and his protocol version first, nothing is received before.
+    /// When reconnecting after a dropped connection, the player then resumes his session
+    /// and receives the whole state of his table again.
     fn on_open(
         &mut self,
-        _: Handshake
+        handshake: Handshake
     ) -> Result<()> {
 
-        println!("Connected.");
+        self.encoding = handshake.response
+            .protocol()?
+            .and_then(Encoding::from_subprotocol)
+            .unwrap_or_default();
+
+        println!("Connected, encoded in {:?}.", self.encoding);
 
         self.channel_sender.send(
-            Event::Connect(self.socket_sender.clone())
+            Event::Connect(self.output())
         ).unwrap();
 
+        self.send(&ClientMessage::Join {
+            name: self.player_name.clone(),
+            client_version: PROTOCOL_VERSION,
+        });
+
+        let session_token = self.session_token_mutex_arc
+            .lock()
+            .unwrap()
+            .clone();
+
+        let session_token = match session_token {
+            Some(session_token) => session_token,
+            None => return Ok(()),
+        };
+
+        self.clear_table();
+
+        self.resuming = true;
+        self.send(&ClientMessage::Resume {
+            session_token,
+        });
+
         Ok(())
     }
 
     /// Called when a message is received from the server.
-    /// Stores the message into a socket message object
-    /// and modifies the client according to the message action.
+    /// Decodes the message and modifies the client according to its kind,
+    /// the messages which cannot be decoded are displayed as errors and ignored.
     ///
     /// # Args:
     ///
@@ -200,74 +638,319 @@ impl Handler for Client {
         message: Message,
     ) -> Result<()> {
 
-        let text_message: &str = &message.into_text().unwrap();
-        let data: SocketMessage = serde_json::from_str(text_message).unwrap();
-
-        if data.action == MessageAction::SendPlayerCard {
-
-            let mut displayed_cards: MutexGuard<Vec<u16>> =
-                self.player_cards_mutex_arc.lock().unwrap();
-            displayed_cards.push(data.card_index);
-
-            let mut remaining_cards_amount: MutexGuard<u16> =
-                self.cards_amount_arc.lock().unwrap();
-            *remaining_cards_amount = data.cards_amount;
-
-            let mut player_points: MutexGuard<u8> =
-                self.player_points_mutex_arc.lock().unwrap();
-            *player_points = data.player_handpoints;
-
+        if message.is_binary() != self.encoding.is_binary() {
+            self.show_error("the server message does not use the negotiated encoding".to_string());
             return Ok(());
         }
 
-        if data.action == MessageAction::SendBankCard {
-
-            let mut bank_cards: MutexGuard<Vec<u16>> =
-                self.bank_cards_mutex_arc.lock().unwrap();
-            bank_cards.push(data.card_index);
-
-            let mut bank_points: MutexGuard<u8> =
-                self.bank_points_mutex_arc.lock().unwrap();
-            *bank_points = data.player_handpoints;
-
-            let mut basic_strategy_action: MutexGuard<MessageAction> =
-                self.basic_strategy_action_mutex_arc.lock().unwrap();
-            let player_cards: MutexGuard<Vec<u16>> =
-                self.player_cards_mutex_arc.lock().unwrap();
-
-            *basic_strategy_action = get_strategic_action(
-                &player_cards,
-                &bank_cards,
-            );
-        }
-
-        if data.action == MessageAction::SendBankCards {
-
-            let mut bank_cards: MutexGuard<Vec<u16>> =
-                self.bank_cards_mutex_arc.lock().unwrap();
-            *bank_cards = data.bank_cards;
-
-            let mut bank_points: MutexGuard<u8> =
-                self.bank_points_mutex_arc.lock().unwrap();
-            *bank_points = data.player_handpoints;
-
-            let mut displayed_bank_cards_amount: MutexGuard<usize> =
-                self.displayed_bank_cards_amount_mutex_arc.lock().unwrap();
-            const DISPLAYED_BANK_CARDS_AMOUNT_AFTER_DRAWING: usize = 2;
-            *displayed_bank_cards_amount = DISPLAYED_BANK_CARDS_AMOUNT_AFTER_DRAWING;
+        let message: ServerMessage = match self.encoding.decode(&message.into_data()) {
+            Ok(message) => message,
+            Err(error) => {
+                self.show_error(format!("the server message cannot be decoded ({})", error));
+                return Ok(());
+            },
+        };
+
+        self.verify_shoe(&message);
+
+        /* the cards and the results of all the players are received by the spectators,
+           only the cards of the other players are received by the seated players */
+        let other_seat = match message {
+            ServerMessage::PlayerCard { seat_index, .. } |
+            ServerMessage::Split { seat_index, .. } |
+            ServerMessage::ActiveHand { seat_index, .. } |
+            ServerMessage::ActiveSeat { seat_index } |
+            ServerMessage::RoundResult { seat_index, .. } => self.seat_index != Some(seat_index),
+            _ => false,
+        };
+
+        match message {
+            ServerMessage::PlayerCard { .. } |
+            ServerMessage::Split { .. } |
+            ServerMessage::ActiveHand { .. } if other_seat => {
+                self.update_other_seat(&message);
+            },
+            ServerMessage::RoundResult { seat_index, result, amount, .. } if other_seat => {
+
+                let seat_index = seat_index as usize;
+
+                let mut seats_results: MutexGuard<Vec<SeatResults>> =
+                    self.seats_results_mutex_arc.lock().unwrap();
+
+                if seats_results.len() <= seat_index {
+                    seats_results.resize(seat_index + 1, vec![]);
+                }
+                seats_results[seat_index].push((
+                    result,
+                    amount,
+                ));
+            },
+            /* the player cannot do anything on the server without a valid name */
+            ServerMessage::Error { code: ErrorCode::InvalidName, message } => {
+
+                self.show_error(message);
+
+                *self.session_token_mutex_arc.lock().unwrap() = None;
+                self.socket_sender.close(CloseCode::Normal).unwrap();
+            },
+            ServerMessage::Error { code, message } => {
+                self.show_error(format!("{} ({:?})", message, code));
+            },
+            ServerMessage::Hello { version } => {
+                println!("Server protocol version {}.", version);
+            },
+            /* the server closes the connection, there is no session to resume with it anymore */
+            ServerMessage::IncompatibleVersion { version } => {
+
+                println!(
+                    "The server protocol version {} is not compatible with the client one {}.",
+                    version,
+                    PROTOCOL_VERSION,
+                );
+
+                *self.session_token_mutex_arc.lock().unwrap() = None;
+            },
+            /* the tables answering the join arrive before the answer to a pending resume request,
+               the session is kept until the resume succeeds or fails */
+            ServerMessage::Tables { error: None, .. } if self.resuming => {},
+            /* the player is into the lobby as long as he does not sit at a table,
+               he has no session to resume there */
+            ServerMessage::Tables { tables, error } => {
+
+                self.resuming = false;
+                self.clear_table();
+
+                *self.session_token_mutex_arc.lock().unwrap() = None;
+                self.shoe_verification = ShoeVerification::new();
+
+                *self.lobby_tables_mutex_arc.lock().unwrap() = Some(tables);
+                *self.lobby_message_mutex_arc.lock().unwrap() = error.unwrap_or_default();
+            },
+            ServerMessage::Welcome { player_id, seat_index, rules, bankroll, cards_amount } => {
+
+                println!(
+                    "Welcome {} (player {}) at the seat {}.",
+                    self.player_name,
+                    player_id,
+                    seat_index,
+                );
+
+                self.seat_index = Some(seat_index);
+                self.resuming = false;
+
+                *self.lobby_tables_mutex_arc.lock().unwrap() = None;
+                self.lobby_message_mutex_arc.lock().unwrap().clear();
+
+                *self.rules_mutex_arc.lock().unwrap() = rules;
+                *self.bankroll_mutex_arc.lock().unwrap() = bankroll;
+                *self.cards_amount_arc.lock().unwrap() = cards_amount;
+            },
+            ServerMessage::Session { session_token } => {
+                *self.session_token_mutex_arc.lock().unwrap() = Some(session_token);
+            },
+            /* the deadline of the decision is kept rather than the countdown,
+               so the remaining time can be drawn at every frame */
+            ServerMessage::Countdown { seconds } => {
+
+                *self.countdown_mutex_arc.lock().unwrap() = match seconds {
+                    0 => None,
+                    seconds => Some(Instant::now() + Duration::from_secs(seconds as u64)),
+                };
+            },
+            ServerMessage::TableSnapshot { snapshot } => {
+
+                self.apply_snapshot(snapshot);
+
+                self.update_basic_strategy_action();
+            },
+            /* the surrender chart depends on the surrender being legal */
+            ServerMessage::Prompt { actions } => {
+
+                *self.legal_actions_mutex_arc.lock().unwrap() = actions;
+
+                self.update_basic_strategy_action();
+            },
+            /* the spectators have no seat, all the seats are the ones of other players */
+            ServerMessage::Spectating { rules, cards_amount } => {
+
+                self.seat_index = None;
+
+                *self.lobby_tables_mutex_arc.lock().unwrap() = None;
+                self.lobby_message_mutex_arc.lock().unwrap().clear();
+
+                *self.rules_mutex_arc.lock().unwrap() = rules;
+                *self.cards_amount_arc.lock().unwrap() = cards_amount;
+            },
+            /* only the seat of another playing player is kept, in order to wait for him */
+            ServerMessage::ActiveSeat { seat_index } => {
+
+                let mut playing_seat: MutexGuard<Option<u8>> =
+                    self.playing_seat_mutex_arc.lock().unwrap();
+
+                *playing_seat = if other_seat {
+                    Some(seat_index)
+                } else {
+                    None
+                };
+            },
+            ServerMessage::NewRound => {
+
+                /* a player who did not play the previous round still
+                   displays its cards until the next round is dealt */
+                self.player_cards_mutex_arc.lock().unwrap().clear();
+                self.bank_cards_mutex_arc.lock().unwrap().clear();
+
+                const DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT: usize = 1;
+                *self.displayed_bank_cards_amount_mutex_arc.lock().unwrap() =
+                    DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT;
+
+                self.round_results_mutex_arc.lock().unwrap().clear();
+                self.seats_cards_mutex_arc.lock().unwrap().clear();
+                self.seats_results_mutex_arc.lock().unwrap().clear();
+                *self.playing_seat_mutex_arc.lock().unwrap() = None;
+            },
+            ServerMessage::PlayerCard { hand_index, card, points, cards_amount, .. } => {
+
+                let hand_index = hand_index as usize;
+
+                let mut displayed_hands: MutexGuard<Vec<Vec<u16>>> =
+                    self.player_cards_mutex_arc.lock().unwrap();
+
+                /* the insurance result of the previous round is dropped
+                   as soon as the first card of the new round is received */
+                if displayed_hands.is_empty() {
+                    *self.active_hand_mutex_arc.lock().unwrap() = 0;
+                    *self.insurance_result_mutex_arc.lock().unwrap() = None;
+                }
+
+                if displayed_hands.len() <= hand_index {
+                    displayed_hands.resize(hand_index + 1, vec![]);
+                }
+                displayed_hands[hand_index].push(card);
+
+                let mut remaining_cards_amount: MutexGuard<u16> =
+                    self.cards_amount_arc.lock().unwrap();
+                *remaining_cards_amount = cards_amount;
+
+                /* the points of the hands of the previous round are dropped
+                   as soon as the cards of the new round are received */
+                let mut player_points: MutexGuard<Vec<u8>> =
+                    self.player_points_mutex_arc.lock().unwrap();
+                player_points.resize(displayed_hands.len(), 0);
+                player_points[hand_index] = points;
+            },
+            ServerMessage::Split { hand_index, .. } => {
+
+                let hand_index = hand_index as usize;
+
+                let mut displayed_hands: MutexGuard<Vec<Vec<u16>>> =
+                    self.player_cards_mutex_arc.lock().unwrap();
+
+                let split_card = match displayed_hands.get_mut(hand_index).and_then(Vec::pop) {
+                    Some(split_card) => split_card,
+                    None => {
+                        self.show_error("the split hand is unknown".to_string());
+                        return Ok(());
+                    },
+                };
+
+                displayed_hands.insert(
+                    hand_index + 1,
+                    vec![split_card],
+                );
+
+                /* both hands only hold one card right after the split */
+                let mut player_points: MutexGuard<Vec<u8>> =
+                    self.player_points_mutex_arc.lock().unwrap();
+                *player_points = get_hands_points(&displayed_hands);
+            },
+            ServerMessage::ActiveHand { hand_index, .. } => {
+
+                *self.active_hand_mutex_arc.lock().unwrap() = hand_index as usize;
+
+                self.update_basic_strategy_action();
+            },
+            /* the shoe is renewed between two rounds, nothing else than the remaining cards changes;
+               the hole card itself is withheld by the server until the bank cards are revealed */
+            ServerMessage::Shuffle { cards_amount } |
+            ServerMessage::HoleCard { cards_amount } => {
+
+                let mut remaining_cards_amount: MutexGuard<u16> =
+                    self.cards_amount_arc.lock().unwrap();
+                *remaining_cards_amount = cards_amount;
+            },
+            ServerMessage::Offer { offer, amount } => {
+
+                *self.offer_mutex_arc.lock().unwrap() = Some((offer, amount));
+
+                /* the early surrender is the first decision of the round */
+                if offer == Offer::Surrender {
+                    self.update_basic_strategy_action();
+                }
+            },
+            ServerMessage::Bankroll { amount } => {
+                *self.bankroll_mutex_arc.lock().unwrap() = amount;
+            },
+            ServerMessage::RoundResult { result, amount, .. } => {
+
+                /* the results are sent in the order of the hands, once the bank cards are revealed */
+                let mut round_results: MutexGuard<Vec<(HandResult, u32)>> =
+                    self.round_results_mutex_arc.lock().unwrap();
+                round_results.push((
+                    result,
+                    amount,
+                ));
+            },
+            ServerMessage::InsuranceResult { amount } => {
+
+                let mut insurance_result: MutexGuard<Option<u32>> =
+                    self.insurance_result_mutex_arc.lock().unwrap();
+                *insurance_result = Some(amount);
+            },
+            ServerMessage::BankCard { card, points, .. } => {
+
+                let mut bank_cards: MutexGuard<Vec<u16>> =
+                    self.bank_cards_mutex_arc.lock().unwrap();
+                bank_cards.push(card);
+
+                let mut bank_points: MutexGuard<u8> =
+                    self.bank_points_mutex_arc.lock().unwrap();
+                *bank_points = points;
+            },
+            ServerMessage::BankCards { cards, points } => {
+
+                *self.playing_seat_mutex_arc.lock().unwrap() = None;
+
+                let mut bank_cards: MutexGuard<Vec<u16>> =
+                    self.bank_cards_mutex_arc.lock().unwrap();
+                *bank_cards = cards;
+
+                let mut bank_points: MutexGuard<u8> =
+                    self.bank_points_mutex_arc.lock().unwrap();
+                *bank_points = points;
+
+                let mut displayed_bank_cards_amount: MutexGuard<usize> =
+                    self.displayed_bank_cards_amount_mutex_arc.lock().unwrap();
+                const DISPLAYED_BANK_CARDS_AMOUNT_AFTER_DRAWING: usize = 2;
+                *displayed_bank_cards_amount = DISPLAYED_BANK_CARDS_AMOUNT_AFTER_DRAWING;
+            },
+            /* the shoe commitments are handled by the verification of the shoe,
+               the message kinds added by a newer minor version are ignored */
+            ServerMessage::ShoeCommitment { .. } |
+            ServerMessage::ShoeReveal { .. } |
+            ServerMessage::Unknown => {},
         }
 
         Ok(())
     }
 
-    /// Called when the server closes the connection.
-    /// Sends a message to the main thread in order to stop the program.
+    /// Called when the connection is closed. The socket thread then tries to resume the session
+    /// of the player if he sits at a table, or stops the program.
     fn on_close(
         &mut self,
         _: CloseCode,
         _: &str
     ) {
-        self.channel_sender.send(Event::Disconnect).unwrap();
+        println!("Disconnected.");
     }
 }
-
@@ -2,6 +2,7 @@
 
 use piston_window::{
     image,
+    rectangle,
     Context,
     G2dTexture,
     G2d,
@@ -15,7 +16,21 @@ use std::sync::{
     Arc,
 };
 
-use crate::message_action::MessageAction;
+use crate::player_action::PlayerAction;
+use crate::client::{
+    get_hands_points,
+    SeatResults,
+};
+
+use blackjack_core::{
+    Card,
+    HandResult,
+    TableSummary,
+};
+use blackjack_protocol::{
+    Action,
+    Offer,
+};
 
 const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 const RED_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
@@ -40,17 +55,17 @@ pub fn display_player_cards(
     vertical_position: f64,
 ) {
 
-    for card_index in 0..cards.len() {
+    /* an unknown card id sent by the server is not displayed */
+    let cards = cards.iter()
+        .filter_map(|card_id| Card::from_wire_id(*card_id));
+
+    for (card_index, card) in cards.enumerate() {
 
         const CARDS_DISTANCE: f64 = 40.0;
         const CARD_DIMENSIONS_SCALE: f64 = 0.5;
-        const ONE_GAME_CARDS_AMOUNT: usize = 52;
 
         image(
-            &all_cards_images[
-                *cards.get(card_index)
-                    .unwrap() as usize % ONE_GAME_CARDS_AMOUNT
-            ],
+            &all_cards_images[card.face_index() as usize],
             context.transform.trans(
                 horizontal_position + card_index as f64 * CARDS_DISTANCE,
                 vertical_position + card_index as f64 * CARDS_DISTANCE,
@@ -72,27 +87,56 @@ pub fn display_player_cards(
 /// `all_cards_images` - the array of all the cards images
 /// `cards` - the cards to display
 /// `displayed_bank_cards_amount` - the amount of cards to display to make the animation
+/// `hole_card` - true if the face-down card of the bank has to be displayed after the face-up card
 pub fn display_bank_cards(
     window: &mut G2d,
     context: &Context,
     all_cards_images: &[G2dTexture],
     cards: &Vec<u16>,
     displayed_bank_cards_amount: usize,
+    hole_card: bool,
 ) {
 
-    for card_index in 0..displayed_bank_cards_amount {
+    const CARDS_DISTANCE: f64 = 40.0;
+    const CARD_DIMENSIONS_SCALE: f64 = 0.5;
+    const BANK_CARD_HORIZONTAL_POSITION: f64 = 300.0;
+    const BANK_CARD_VERTICAL_POSITION: f64 = 100.0;
+
+    if hole_card {
+
+        /* there is no card back texture,
+           the hole card is drawn as a plain rectangle
+           with the scaled dimensions of the cards textures */
+        const HOLE_CARD_COLOR: [f32; 4] = [0.6, 0.1, 0.1, 1.0];
+        const CARD_WIDTH: f64 = 148.0;
+        const CARD_HEIGHT: f64 = 215.0;
+        const HOLE_CARD_INDEX: f64 = 1.0;
+
+        rectangle(
+            HOLE_CARD_COLOR,
+            [
+                0.0,
+                0.0,
+                CARD_WIDTH * CARD_DIMENSIONS_SCALE,
+                CARD_HEIGHT * CARD_DIMENSIONS_SCALE,
+            ],
+            context.transform.trans(
+                BANK_CARD_HORIZONTAL_POSITION + HOLE_CARD_INDEX * CARDS_DISTANCE,
+                BANK_CARD_VERTICAL_POSITION + HOLE_CARD_INDEX * CARDS_DISTANCE,
+            ),
+            window,
+        );
+    }
+
+    /* the displayed amount can exceed the received cards before the first bank card arrives */
+    let cards = cards.iter()
+        .take(displayed_bank_cards_amount)
+        .filter_map(|card_id| Card::from_wire_id(*card_id));
 
-        const CARDS_DISTANCE: f64 = 40.0;
-        const CARD_DIMENSIONS_SCALE: f64 = 0.5;
-        const ONE_GAME_CARDS_AMOUNT: usize = 52;
-        const BANK_CARD_HORIZONTAL_POSITION: f64 = 300.0;
-        const BANK_CARD_VERTICAL_POSITION: f64 = 100.0;
+    for (card_index, card) in cards.enumerate() {
 
         image(
-            &all_cards_images[
-                *cards.get(card_index)
-                    .unwrap() as usize % ONE_GAME_CARDS_AMOUNT
-            ],
+            &all_cards_images[card.face_index() as usize],
             context.transform.trans(
                 BANK_CARD_HORIZONTAL_POSITION + card_index as f64 * CARDS_DISTANCE,
                 BANK_CARD_VERTICAL_POSITION + card_index as f64 * CARDS_DISTANCE,
@@ -141,6 +185,368 @@ pub fn display_remaining_cards_amount(
     ).unwrap();
 }
 
+/// Displays the bankroll and the bet of the player, with the betting keys while the player can bet.
+///
+/// # Args:
+///
+/// `window` - the window where to draw
+/// `context` - the rendering loop context
+/// `glyphs` - the text rendering Piston glyph
+/// `bankroll` - the bankroll of the player
+/// `bet_amount` - the amount bet by the player for the round
+/// `betting` - true if the player is placing his bet for the next round
+pub fn display_bankroll(
+    window: &mut G2d,
+    context: &Context,
+    glyphs: &mut Glyphs,
+    bankroll: u32,
+    bet_amount: u32,
+    betting: bool,
+) {
+
+    let message = if betting {
+        format!(
+            "Bankroll {} - Bet {} (Up/Down, Enter to DEAL, Backspace to LEAVE)",
+            bankroll,
+            bet_amount,
+        )
+    } else {
+        format!(
+            "Bankroll {} - Bet {}",
+            bankroll,
+            bet_amount,
+        )
+    };
+
+    const BANKROLL_FONT_SIZE: u32 = 16;
+    const BANKROLL_HORIZONTAL_POSITION: f64 = 10.0;
+    const BANKROLL_VERTICAL_POSITION: f64 = 510.0;
+
+    text::Text::new_color(
+        WHITE_COLOR,
+        BANKROLL_FONT_SIZE,
+    ).draw(
+        &message,
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            BANKROLL_HORIZONTAL_POSITION,
+            BANKROLL_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
+}
+
+/// Displays the points of the hands of the other players sitting at the table, one line per player,
+/// and the seat of the other player currently playing if any.
+///
+/// # Args:
+///
+/// `window` - the window where to draw
+/// `context` - the rendering loop context
+/// `glyphs` - the text rendering Piston glyph
+/// `seats_points` - the points of every hand of every other player, indexed by seat
+/// `playing_seat` - the seat of the other player currently playing, if any
+pub fn display_seats_information(
+    window: &mut G2d,
+    context: &Context,
+    glyphs: &mut Glyphs,
+    seats_points: &[Vec<u8>],
+    playing_seat: Option<u8>,
+) {
+
+    const SEATS_FONT_SIZE: u32 = 16;
+    const SEATS_HORIZONTAL_POSITION: f64 = 600.0;
+    const SEATS_VERTICAL_POSITION: f64 = 150.0;
+    const SEATS_LINE_HEIGHT: f64 = 25.0;
+
+    let seats_lines = seats_points
+        .iter()
+        .enumerate()
+        .filter(|(_, hands_points)| !hands_points.is_empty());
+
+    let mut lines_amount = 0;
+
+    for (line_index, (seat_index, hands_points)) in seats_lines.enumerate() {
+
+        let points: Vec<String> = hands_points
+            .iter()
+            .map(|points| points.to_string())
+            .collect();
+
+        text::Text::new_color(
+            WHITE_COLOR,
+            SEATS_FONT_SIZE,
+        ).draw(
+            &format!("Seat {}: {}", seat_index + 1, points.join(" / ")),
+            glyphs,
+            &context.draw_state,
+            context.transform.trans(
+                SEATS_HORIZONTAL_POSITION,
+                SEATS_VERTICAL_POSITION + line_index as f64 * SEATS_LINE_HEIGHT,
+            ),
+            window,
+        ).unwrap();
+
+        lines_amount += 1;
+    }
+
+    let playing_seat = match playing_seat {
+        Some(playing_seat) => playing_seat,
+        None => return,
+    };
+
+    text::Text::new_color(
+        WHITE_COLOR,
+        SEATS_FONT_SIZE,
+    ).draw(
+        &format!("Seat {} is playing...", playing_seat + 1),
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            SEATS_HORIZONTAL_POSITION,
+            SEATS_VERTICAL_POSITION + lines_amount as f64 * SEATS_LINE_HEIGHT,
+        ),
+        window,
+    ).unwrap();
+}
+
+/// Displays the lobby: one line per table with its name, its players and its main rules,
+/// the lobby keys and the reason of the last failed lobby request if any.
+/// The full tables are displayed in red.
+///
+/// # Args:
+///
+/// `window` - the window where to draw
+/// `context` - the rendering loop context
+/// `glyphs` - the text rendering Piston glyph
+/// `tables` - the summaries of all the tables of the lobby
+/// `lobby_message` - the reason of the last failed lobby request, empty if none
+/// `spectating` - true if the tables are opened read-only, as a spectator
+pub fn display_lobby(
+    window: &mut G2d,
+    context: &Context,
+    glyphs: &mut Glyphs,
+    tables: &[TableSummary],
+    lobby_message: &str,
+    spectating: bool,
+) {
+
+    const LOBBY_FONT_SIZE: u32 = 16;
+    const LOBBY_HORIZONTAL_POSITION: f64 = 10.0;
+    const TABLES_VERTICAL_POSITION: f64 = 150.0;
+    const TABLES_LINE_HEIGHT: f64 = 25.0;
+    const KEYS_VERTICAL_POSITION: f64 = 510.0;
+    const MESSAGE_VERTICAL_POSITION: f64 = 540.0;
+
+    /* only the nine first tables can be joined with the digit keys */
+    const MAX_DISPLAYED_TABLES_AMOUNT: usize = 9;
+
+    for (table_index, table) in tables.iter().take(MAX_DISPLAYED_TABLES_AMOUNT).enumerate() {
+
+        let rules = &table.rules;
+        let line = format!(
+            "{}. {} - {}/{} players - bets {} to {} - {} decks, blackjack pays {}",
+            table_index + 1,
+            table.name,
+            table.players_amount,
+            rules.seats_amount,
+            rules.min_bet,
+            rules.max_bet,
+            rules.decks_amount,
+            rules.blackjack_payout.label(),
+        );
+
+        let color = if table.is_full() {
+            RED_COLOR
+        } else {
+            WHITE_COLOR
+        };
+
+        text::Text::new_color(
+            color,
+            LOBBY_FONT_SIZE,
+        ).draw(
+            &line,
+            glyphs,
+            &context.draw_state,
+            context.transform.trans(
+                LOBBY_HORIZONTAL_POSITION,
+                TABLES_VERTICAL_POSITION + table_index as f64 * TABLES_LINE_HEIGHT,
+            ),
+            window,
+        ).unwrap();
+    }
+
+    let keys_message = if spectating {
+        "1-9 to WATCH a table, L to REFRESH"
+    } else {
+        "1-9 to JOIN a table, C to CREATE a table, L to REFRESH"
+    };
+
+    text::Text::new_color(
+        WHITE_COLOR,
+        LOBBY_FONT_SIZE,
+    ).draw(
+        keys_message,
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            LOBBY_HORIZONTAL_POSITION,
+            KEYS_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
+
+    text::Text::new_color(
+        RED_COLOR,
+        LOBBY_FONT_SIZE,
+    ).draw(
+        lobby_message,
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            LOBBY_HORIZONTAL_POSITION,
+            MESSAGE_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
+}
+
+/// Displays the hands of every player of the watched table side by side, for the spectators.
+/// Every seat is labelled with the points of its hands, and with the net amount won or lost
+/// once the round is finished. The playing seat is marked.
+///
+/// # Args:
+///
+/// `window` - the window where to draw
+/// `context` - the rendering loop context
+/// `glyphs` - the text rendering Piston glyph
+/// `all_cards_images` - the array of all the cards images
+/// `seats_cards` - the cards of every hand of every player, indexed by seat
+/// `seats_results` - the results of every hand of every player, indexed by seat, empty during the round
+/// `playing_seat` - the seat of the player currently playing, if any
+pub fn display_spectated_seats(
+    window: &mut G2d,
+    context: &Context,
+    glyphs: &mut Glyphs,
+    all_cards_images: &[G2dTexture],
+    seats_cards: &[Vec<Vec<u16>>],
+    seats_results: &[SeatResults],
+    playing_seat: Option<u8>,
+) {
+
+    const SEATS_FONT_SIZE: u32 = 16;
+    const SEATS_HORIZONTAL_POSITION: f64 = 10.0;
+    const SEATS_DISTANCE: f64 = 110.0;
+    const SEAT_LABEL_VERTICAL_POSITION: f64 = 270.0;
+    const SEAT_RESULT_VERTICAL_POSITION: f64 = 250.0;
+    const SEAT_CARDS_VERTICAL_POSITION: f64 = 280.0;
+    const SPLIT_HANDS_HORIZONTAL_DISTANCE: f64 = 20.0;
+    const SPLIT_HANDS_VERTICAL_DISTANCE: f64 = 60.0;
+    const KEYS_HORIZONTAL_POSITION: f64 = 10.0;
+    const KEYS_VERTICAL_POSITION: f64 = 570.0;
+
+    for (seat_index, hands) in seats_cards.iter().enumerate() {
+
+        if hands.is_empty() {
+            continue;
+        }
+
+        let seat_position = SEATS_HORIZONTAL_POSITION + seat_index as f64 * SEATS_DISTANCE;
+
+        let points: Vec<String> = get_hands_points(hands)
+            .iter()
+            .map(|points| points.to_string())
+            .collect();
+
+        let marker = if playing_seat == Some(seat_index as u8) {
+            "> "
+        } else {
+            ""
+        };
+
+        text::Text::new_color(
+            WHITE_COLOR,
+            SEATS_FONT_SIZE,
+        ).draw(
+            &format!("{}Seat {}: {}", marker, seat_index + 1, points.join(" / ")),
+            glyphs,
+            &context.draw_state,
+            context.transform.trans(
+                seat_position,
+                SEAT_LABEL_VERTICAL_POSITION,
+            ),
+            window,
+        ).unwrap();
+
+        let results = seats_results
+            .get(seat_index)
+            .filter(|results| !results.is_empty());
+
+        if let Some(results) = results {
+
+            let (won_amount, lost_amount) = results.iter().fold(
+                (0, 0),
+                |(won, lost), (result, amount)| {
+                    if result.is_loss() {
+                        (won, lost + amount)
+                    } else {
+                        (won + amount, lost)
+                    }
+                },
+            );
+
+            let (result_message, result_color) = if lost_amount > won_amount {
+                (format!("-{}", lost_amount - won_amount), RED_COLOR)
+            } else {
+                (format!("+{}", won_amount - lost_amount), WHITE_COLOR)
+            };
+
+            text::Text::new_color(
+                result_color,
+                SEATS_FONT_SIZE,
+            ).draw(
+                &result_message,
+                glyphs,
+                &context.draw_state,
+                context.transform.trans(
+                    seat_position,
+                    SEAT_RESULT_VERTICAL_POSITION,
+                ),
+                window,
+            ).unwrap();
+        }
+
+        /* the split hands of one seat overlap, each one slightly shifted */
+        for (hand_index, hand_cards) in hands.iter().enumerate() {
+
+            display_player_cards(
+                window,
+                context,
+                all_cards_images,
+                hand_cards,
+                seat_position + hand_index as f64 * SPLIT_HANDS_HORIZONTAL_DISTANCE,
+                SEAT_CARDS_VERTICAL_POSITION + hand_index as f64 * SPLIT_HANDS_VERTICAL_DISTANCE,
+            );
+        }
+    }
+
+    text::Text::new_color(
+        WHITE_COLOR,
+        SEATS_FONT_SIZE,
+    ).draw(
+        "Spectating (Backspace to LEAVE, F5 to REFRESH)",
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            KEYS_HORIZONTAL_POSITION,
+            KEYS_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
+}
+
 /// Displays the current player name.
 ///
 /// # Args:
@@ -175,79 +581,94 @@ pub fn display_player_name(
     ).unwrap();
 }
 
-/// Displays the current player information about his possible actions.
+/// Returns the message of one hand result with the amount won or lost.
+///
+/// # Args:
+///
+/// `result` - the result of the hand
+/// `amount` - the amount won or lost by the hand
+fn get_result_message(
+    result: HandResult,
+    amount: u32,
+) -> String {
+    match result {
+        HandResult::Win => format!("Player wins ! +{}", amount),
+        HandResult::Loss => format!("Dealer wins -{}", amount),
+        HandResult::Push => "Push".to_string(),
+        HandResult::Blackjack => format!("Blackjack ! +{}", amount),
+        HandResult::Surrender => format!("Surrendered -{}", amount),
+        HandResult::Bust => format!("Bust ! -{}", amount),
+    }
+}
+
+/// Displays the current player information about his legal actions,
+/// or the results of his hands sent by the server once the round is finished.
 ///
 /// # Args:
 ///
 /// `window` - the window where to draw
 /// `context` - the rendering loop context
 /// `glyphs` - the text rendering Piston glyph
-/// `player_points` - the amount of the player hand points
-/// `bank_points` - the amount of the bank hand points
-/// `player_cards_amount` - the amount of cards of the player
+/// `round_results` - the result and the amount won or lost of every hand, empty during the round
+/// `legal_actions` - the actions the player has been prompted to take
 /// `bank_cards_amount` - the amount of cards of the bank
 /// `displayed_bank_cards_amount` - the amount of displayed bank cards
 pub fn display_information(
     window: &mut G2d,
     context: &Context,
     glyphs: &mut Glyphs,
-    player_points: &Arc<Mutex<u8>>,
-    bank_points: &Arc<Mutex<u8>>,
-    player_cards_amount: usize,
+    round_results: &[(HandResult, u32)],
+    legal_actions: &[Action],
     bank_cards_amount: usize,
     displayed_bank_cards_amount: usize,
 ) {
-    const MIN_BANK_HAND_POINTS: u8 = 17;
-    const MAX_VALID_HAND_POINTS: u8 = 21;
-    const ALLOWED_DOUBLE_CARDS_AMOUNT: usize = 2;
-
-    const HIT_STAND_DOUBLE_MESSAGE: &str = "Enter to HIT, Space to STAND, D to DOUBLE DOWN";
-    const HIT_STAND_MESSAGE: &str = "Enter to HIT, Space to STAND";
-    const CONTINUE_MESSAGE: &str = "21 ! Enter to CONTINUE";
-    const BANK_WINS_MESSAGE: &str = "Dealer wins";
-    const PLAYER_WINS_MESSAGE: &str = "Player wins !";
-    const PUSH_MESSAGE: &str = "Push";
-    const BURST_MESSAGE: &str = "Burst ! Press Enter";
+    const ACTIONS_KEYS: [(Action, &str); 5] = [
+        (Action::Hit, "Enter to HIT"),
+        (Action::Stand, "Space to STAND"),
+        (Action::DoubleDown, "D to DOUBLE DOWN"),
+        (Action::Split, "S to SPLIT"),
+        (Action::Surrender, "R to SURRENDER"),
+    ];
     const DEALER_PLAYING_MESSAGE: &str = "Waiting for the dealer to play...";
-
-    let player_points = player_points.lock().unwrap();
-    let bank_points = bank_points.lock().unwrap();
+    const WAITING_MESSAGE: &str = "Waiting for the other players...";
 
     let displayed_message = if displayed_bank_cards_amount != bank_cards_amount {
-        DEALER_PLAYING_MESSAGE
-    }
-    else if *player_points > MAX_VALID_HAND_POINTS {
-        BURST_MESSAGE
+        DEALER_PLAYING_MESSAGE.to_string()
     }
-    else if *bank_points >= MIN_BANK_HAND_POINTS &&
-        *bank_points <= MAX_VALID_HAND_POINTS &&
-        *player_points < *bank_points {
-        BANK_WINS_MESSAGE
+    else if !round_results.is_empty() {
+        round_results.iter()
+            .map(|(result, amount)| get_result_message(*result, *amount))
+            .collect::<Vec<String>>()
+            .join(" / ")
     }
-    else if *bank_points >= MIN_BANK_HAND_POINTS &&
-        *bank_points <= MAX_VALID_HAND_POINTS &&
-        *player_points == *bank_points {
-        PUSH_MESSAGE
-    }
-    else if (
-        *bank_points >= MIN_BANK_HAND_POINTS &&
-        *player_points > *bank_points
-    ) || (
-        *bank_points > MAX_VALID_HAND_POINTS &&
-        *player_points <= MAX_VALID_HAND_POINTS
-    ) {
-        PLAYER_WINS_MESSAGE
-    }
-    else if *player_points == MAX_VALID_HAND_POINTS {
-        CONTINUE_MESSAGE
-    }
-    else if player_cards_amount != ALLOWED_DOUBLE_CARDS_AMOUNT {
-        HIT_STAND_MESSAGE
-    } else {
-        HIT_STAND_DOUBLE_MESSAGE
+    else {
+
+        let actions_keys: Vec<&str> = ACTIONS_KEYS.iter()
+            .filter(|(action, _)| legal_actions.contains(action))
+            .map(|(_, keys)| *keys)
+            .collect();
+
+        if actions_keys.is_empty() {
+            WAITING_MESSAGE.to_string()
+        } else {
+            actions_keys.join(", ")
+        }
     };
 
-    let message_color = if *player_points > MAX_VALID_HAND_POINTS {
+    /* the message is red when the player lost more than he won during the round */
+    let (won_amount, lost_amount) = round_results.iter().fold(
+        (0, 0),
+        |(won, lost), (result, amount)| {
+            if result.is_loss() {
+                (won, lost + amount)
+            } else {
+                (won + amount, lost)
+            }
+        },
+    );
+
+    let message_color = if lost_amount > won_amount &&
+        displayed_bank_cards_amount == bank_cards_amount {
         RED_COLOR
     } else {
         WHITE_COLOR
@@ -260,7 +681,7 @@ pub fn display_information(
         message_color,
         INFO_FONT_SIZE,
     ).draw(
-        displayed_message,
+        &displayed_message,
         glyphs,
         &context.draw_state,
         context.transform.trans(
@@ -284,8 +705,8 @@ pub fn display_basic_strategy_information(
     window: &mut G2d,
     context: &Context,
     glyphs: &mut Glyphs,
-    basic_strategy_action: MessageAction,
-    last_player_action: MessageAction,
+    basic_strategy_action: PlayerAction,
+    last_player_action: PlayerAction,
 ) {
 
     const CORRECT_ACTION: &str = "Your action is correct !";
@@ -315,6 +736,58 @@ pub fn display_basic_strategy_information(
     ).unwrap();
 }
 
+/// Displays the pending offer while the player has to decide, or the insurance result once settled.
+///
+/// # Args:
+///
+/// `window` - the window where to draw
+/// `context` - the rendering loop context
+/// `glyphs` - the text rendering Piston glyph
+/// `offer` - the kind of offer (insurance, even money or early surrender) with its amount, if any
+/// `insurance_result` - the amount returned by the insurance once settled, 0 if lost
+pub fn display_offer_information(
+    window: &mut G2d,
+    context: &Context,
+    glyphs: &mut Glyphs,
+    offer: Option<(Offer, u32)>,
+    insurance_result: Option<u32>,
+) {
+
+    let message = match offer {
+        Some((Offer::EvenMoney, amount)) => {
+            format!("Even money for {} ? I to TAKE, N to DECLINE", amount)
+        },
+        Some((Offer::Surrender, amount)) => {
+            format!("Surrender for {} back ? R to SURRENDER, N to DECLINE", amount)
+        },
+        Some((_, stake)) => {
+            format!("Insurance for {} ? I to TAKE, N to DECLINE", stake)
+        },
+        None => match insurance_result {
+            Some(0) => "Insurance lost".to_string(),
+            Some(amount) => format!("Insurance pays {}", amount),
+            None => return,
+        },
+    };
+
+    const OFFER_INFORMATION_HORIZONTAL_POSITION: f64 = 10.0;
+    const OFFER_INFORMATION_VERTICAL_POSITION: f64 = 540.0;
+
+    text::Text::new_color(
+        WHITE_COLOR,
+        INFO_FONT_SIZE,
+    ).draw(
+        &message,
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            OFFER_INFORMATION_HORIZONTAL_POSITION,
+            OFFER_INFORMATION_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
+}
+
 /// Displays the current bank points amount.
 ///
 /// # Args:
@@ -350,37 +823,37 @@ pub fn display_bank_points(
     ).unwrap();
 }
 
-/// Displays the current hand points amount.
+/// Displays the current hand points amount, and the seconds left to take the awaited decision next to them.
 ///
 /// # Args:
 ///
 /// `window` - the window where to draw
 /// `context` - the rendering loop context
 /// `glyphs` - the text rendering Piston glyph
-/// `player_points` - the hand points amount
+/// `player_points` - the current hand points amount
+/// `countdown` - the seconds left to decide, if the server waits for a decision of the player
 pub fn display_player_points(
     window: &mut G2d,
     context: &Context,
     glyphs: &mut Glyphs,
-    player_points: &Arc<Mutex<u8>>,
+    player_points: u8,
+    countdown: Option<u64>,
 ) {
     const POINTS_FONT_SIZE: u32 = 32;
     const POINTS_HORIZONTAL_POSITION: f64 = 200.0;
     const POINTS_VERTICAL_POSITION: f64 = 400.0;
 
-    let player_points = player_points.lock().unwrap();
-
     const MAX_HAND_POINTS: u8 = 21;
 
     text::Text::new_color(
-        if *player_points > MAX_HAND_POINTS {
+        if player_points > MAX_HAND_POINTS {
             RED_COLOR
         } else {
             WHITE_COLOR
         },
         POINTS_FONT_SIZE,
     ).draw(
-        &*player_points.to_string(),
+        &player_points.to_string(),
         glyphs,
         &context.draw_state,
         context.transform.trans(
@@ -389,6 +862,35 @@ pub fn display_player_points(
         ),
         window,
     ).unwrap();
+
+    let countdown = match countdown {
+        Some(countdown) => countdown,
+        None => return,
+    };
+
+    const COUNTDOWN_FONT_SIZE: u32 = 16;
+    const COUNTDOWN_HORIZONTAL_POSITION: f64 = 250.0;
+    const COUNTDOWN_VERTICAL_POSITION: f64 = 400.0;
+
+    const COUNTDOWN_WARNING_SECONDS: u64 = 5;
+
+    text::Text::new_color(
+        if countdown <= COUNTDOWN_WARNING_SECONDS {
+            RED_COLOR
+        } else {
+            WHITE_COLOR
+        },
+        COUNTDOWN_FONT_SIZE,
+    ).draw(
+        &format!("({}s)", countdown),
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            COUNTDOWN_HORIZONTAL_POSITION,
+            COUNTDOWN_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
 }
 
 /// Displays the main title.
@@ -421,3 +923,50 @@ pub fn display_title(
         window,
     ).unwrap();
 }
+
+/// Displays the given error into a red banner at the top of the window.
+///
+/// # Args:
+///
+/// `window` - the window where to draw
+/// `context` - the rendering loop context
+/// `glyphs` - the text rendering Piston glyph
+/// `error` - the description of the error
+pub fn display_error(
+    window: &mut G2d,
+    context: &Context,
+    glyphs: &mut Glyphs,
+    error: &str,
+) {
+    const ERROR_BANNER_WIDTH: f64 = 800.0;
+    const ERROR_BANNER_HEIGHT: f64 = 30.0;
+    const ERROR_FONT_SIZE: u32 = 16;
+    const ERROR_HORIZONTAL_POSITION: f64 = 10.0;
+    const ERROR_VERTICAL_POSITION: f64 = 20.0;
+
+    rectangle(
+        RED_COLOR,
+        [
+            0.0,
+            0.0,
+            ERROR_BANNER_WIDTH,
+            ERROR_BANNER_HEIGHT,
+        ],
+        context.transform,
+        window,
+    );
+
+    text::Text::new_color(
+        WHITE_COLOR,
+        ERROR_FONT_SIZE,
+    ).draw(
+        error,
+        glyphs,
+        &context.draw_state,
+        context.transform.trans(
+            ERROR_HORIZONTAL_POSITION,
+            ERROR_VERTICAL_POSITION,
+        ),
+        window,
+    ).unwrap();
+}
@@ -1,9 +1,9 @@
 //! Event enumeration.
 
-use ws::Sender;
+use crate::output::Output;
 
 #[derive(PartialEq)]
 pub enum Event {
-    Connect(Sender),
+    Connect(Output),
     Disconnect,
 }
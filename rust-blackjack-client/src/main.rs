@@ -2,16 +2,18 @@
 
 extern crate piston_window;
 extern crate ws;
+extern crate url;
 extern crate serde;
-extern crate serde_json;
-#[macro_use] extern crate serde_derive;
+extern crate blackjack_core;
+extern crate blackjack_protocol;
 
 mod cards;
 mod event;
 mod client;
-mod message_action;
-mod socket_message;
+mod output;
+mod player_action;
 mod display;
+mod shoe_verification;
 
 use piston_window::{
     clear,
@@ -24,11 +26,9 @@ use piston_window::{
     TextureSettings,
 };
 
-use ws::{
-    Sender,
-    connect,
-};
+use ws::connect;
 
+use std::env;
 use std::io::stdin;
 use std::sync::{
     Mutex,
@@ -42,10 +42,27 @@ use std::time::{
     Instant,
 };
 
-use client::Client;
+use blackjack_core::{
+    HandResult,
+    TableRules,
+    TableSummary,
+};
+use blackjack_protocol::{
+    Action,
+    ClientMessage,
+    Encoding,
+    Offer,
+};
+
+use client::{
+    Client,
+    get_hands_points,
+    SeatResults,
+};
 use event::Event;
-use message_action::MessageAction;
-use socket_message::SocketMessage;
+use output::Output;
+use player_action::PlayerAction;
+use shoe_verification::ShoeVerification;
 use display::{
     display_player_cards,
     display_bank_cards,
@@ -56,69 +73,182 @@ use display::{
     display_bank_points,
     display_player_points,
     display_title,
+    display_offer_information,
+    display_bankroll,
+    display_seats_information,
+    display_lobby,
+    display_spectated_seats,
+    display_error,
 };
 
+/// Sends the given message to the server.
+///
+/// # Args:
+///
+/// `sender` - the output of the connection in order to send messages to the server
+/// `message` - the message to send
+fn send_message(
+    sender: &Output,
+    message: &ClientMessage,
+) {
+    sender.send(message);
+}
+
 /// Asks a card to the server, this is a "hit" process. Refactored here as used multiple times.
 ///
+/// The bet of a new round is placed instead if the player is prompted to bet.
+///
 /// # Args:
 ///
-/// `sender` - the web socket sender in order to send messages to the server
-/// `bank_points_mutex_arc` - the bank points amount
-/// `player_points_mutex_arc` - the player points amount
-/// `player_cards` - the current player cards
+/// `sender` - the output of the connection in order to send messages to the server
+/// `legal_actions` - the actions the player has been prompted to take
+/// `player_cards` - the current player cards, one list of cards per hand
 /// `bank_cards` - the current bank cards
+/// `round_results` - the results of the player hands, empty until the round is finished
 /// `displayed_bank_cards_amount` - the current expected amount of bank cards to be displayed
+/// `bet_amount` - the amount to bet if a new round is requested
 fn request_card(
-    sender: &Sender,
-    message_action: MessageAction,
-    bank_points_mutex_arc: &Arc<Mutex<u8>>,
-    player_points_mutex_arc: &Arc<Mutex<u8>>,
-    player_cards: &mut Vec<u16>,
+    sender: &Output,
+    legal_actions: &[Action],
+    player_cards: &mut Vec<Vec<u16>>,
     bank_cards: &mut Vec<u16>,
+    round_results: &mut Vec<(HandResult, u32)>,
     displayed_bank_cards_amount: &mut usize,
+    bet_amount: u32,
 ) {
-    let mut message = SocketMessage {
-        action: message_action,
-        card_index: 0,
-        cards_amount: 0,
-        text: "".to_string(),
-        player_handpoints: 0,
-        bank_cards: vec![],
-    };
-
-    let bank_points = bank_points_mutex_arc.lock().unwrap();
-    let player_points = player_points_mutex_arc.lock().unwrap();
-
-    const BANK_MAX_HAND_POINTS: u8 = 17;
-    const PLAYER_MAX_HAND_POINTS: u8 = 21;
 
-    if *bank_points >= BANK_MAX_HAND_POINTS {
+    if legal_actions.contains(&Action::PlaceBet) {
 
         const DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT: usize = 1;
         *displayed_bank_cards_amount = DEFAULT_DISPLAYED_BANK_CARDS_AMOUNT;
 
         player_cards.clear();
         bank_cards.clear();
+        round_results.clear();
 
-        message.action = MessageAction::Restart;
+        send_message(
+            sender,
+            &ClientMessage::PlaceBet {
+                amount: bet_amount,
+            },
+        );
     }
-    else if *player_points >= PLAYER_MAX_HAND_POINTS {
-        message.action = MessageAction::Continue;
+    else if legal_actions.contains(&Action::Hit) {
+        send_message(sender, &ClientMessage::Hit);
     }
+}
+
+/// Sends the lobby request matching the given key, if any: the digit keys join (or watch) the matching table,
+/// C opens a new table with the default rules and L refreshes the tables list.
+/// The spectators cannot open tables.
+///
+/// # Args:
+///
+/// `sender` - the output of the connection in order to send messages to the server
+/// `key` - the pressed key
+/// `player_name` - the name of the player, used to name the opened tables
+/// `spectating` - true if the tables are opened read-only, as a spectator
+fn send_lobby_request(
+    sender: &Output,
+    key: Key,
+    player_name: &str,
+    spectating: bool,
+) {
 
-    let message = serde_json::to_string(&message).unwrap();
-    sender.send(message).unwrap();
+    const TABLES_KEYS: [Key; 9] = [
+        Key::D1,
+        Key::D2,
+        Key::D3,
+        Key::D4,
+        Key::D5,
+        Key::D6,
+        Key::D7,
+        Key::D8,
+        Key::D9,
+    ];
+
+    let message = if let Some(table_index) = TABLES_KEYS.iter().position(|table_key| *table_key == key) {
+
+        let table_index = table_index as u16;
+
+        if spectating {
+            ClientMessage::WatchTable { table_index }
+        } else {
+            ClientMessage::JoinTable { table_index }
+        }
+    }
+    else if key == Key::C && !spectating {
+        ClientMessage::CreateTable {
+            name: format!("{}'s table", player_name),
+            rules: TableRules::default(),
+        }
+    }
+    else if key == Key::L {
+        ClientMessage::ListTables
+    }
+    else {
+        return;
+    };
+
+    send_message(sender, &message);
+}
+
+/// Leaves the current table (or stops watching it) in order to go back to the lobby.
+///
+/// # Args:
+///
+/// `sender` - the output of the connection in order to send messages to the server
+fn send_leave_request(sender: &Output) {
+
+    send_message(sender, &ClientMessage::LeaveTable);
+}
+
+/// Requests the complete state of the current table, in order to rebuild the displayed table from it.
+///
+/// # Args:
+///
+/// `sender` - the output of the connection in order to send messages to the server
+fn send_snapshot_request(sender: &Output) {
+
+    send_message(sender, &ClientMessage::RequestSnapshot);
 }
 
 fn main() {
 
+    /* the tables are opened read-only with the spectate flag */
+    const SPECTATE_FLAG: &str = "--spectate";
+    let spectating = env::args().any(|argument| argument == SPECTATE_FLAG);
+
+    /* the messages are encoded in JSON, unless the binary flag asks the server for MessagePack */
+    const BINARY_FLAG: &str = "--binary";
+    let requested_encoding = if env::args().any(|argument| argument == BINARY_FLAG) {
+        Encoding::MessagePack
+    } else {
+        Encoding::Json
+    };
+
     let player_cards_mutex_arc = Arc::new(Mutex::new(vec![]));
     let bank_cards_mutex_arc = Arc::new(Mutex::new(vec![]));
-    let player_points_mutex_arc: Arc<Mutex<u8>> = Arc::new(Mutex::new(0));
+    let player_points_mutex_arc: Arc<Mutex<Vec<u8>>> = Arc::new(Mutex::new(vec![]));
+    let active_hand_mutex_arc: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
     let bank_points_mutex_arc: Arc<Mutex<u8>> = Arc::new(Mutex::new(0));
     let remaining_cards_amount_arc: Arc<Mutex<u16>> = Arc::new(Mutex::new(0));
     let displayed_bank_cards_amount_mutex_arc: Arc<Mutex<usize>> = Arc::new(Mutex::new(1));
-    let basic_strategy_action_mutex_arc: Arc<Mutex<MessageAction>> = Arc::new(Mutex::new(MessageAction::Hit));
+    let basic_strategy_action_mutex_arc: Arc<Mutex<PlayerAction>> = Arc::new(Mutex::new(PlayerAction::Hit));
+    let rules_mutex_arc: Arc<Mutex<TableRules>> = Arc::new(Mutex::new(TableRules::default()));
+    let offer_mutex_arc: Arc<Mutex<Option<(Offer, u32)>>> = Arc::new(Mutex::new(None));
+    let insurance_result_mutex_arc: Arc<Mutex<Option<u32>>> = Arc::new(Mutex::new(None));
+    let round_results_mutex_arc: Arc<Mutex<Vec<(HandResult, u32)>>> = Arc::new(Mutex::new(vec![]));
+    let bankroll_mutex_arc: Arc<Mutex<u32>> = Arc::new(Mutex::new(0));
+    let seats_cards_mutex_arc: Arc<Mutex<Vec<Vec<Vec<u16>>>>> = Arc::new(Mutex::new(vec![]));
+    let seats_results_mutex_arc: Arc<Mutex<Vec<SeatResults>>> = Arc::new(Mutex::new(vec![]));
+    let playing_seat_mutex_arc: Arc<Mutex<Option<u8>>> = Arc::new(Mutex::new(None));
+    let lobby_tables_mutex_arc: Arc<Mutex<Option<Vec<TableSummary>>>> = Arc::new(Mutex::new(None));
+    let lobby_message_mutex_arc: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+    let session_token_mutex_arc: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let countdown_mutex_arc: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
+    let error_mutex_arc: Arc<Mutex<Option<(String, Instant)>>> = Arc::new(Mutex::new(None));
+    let legal_actions_mutex_arc: Arc<Mutex<Vec<Action>>> = Arc::new(Mutex::new(vec![]));
 
     println!("Player name: ");
     let mut player_name: String = String::new();
@@ -137,53 +267,110 @@ fn main() {
     let player_cards_mutex_arc_clone = player_cards_mutex_arc.clone();
     let bank_cards_mutex_arc_clone = bank_cards_mutex_arc.clone();
     let player_points_mutex_arc_clone = player_points_mutex_arc.clone();
+    let active_hand_mutex_arc_clone = active_hand_mutex_arc.clone();
     let bank_points_mutex_arc_clone = bank_points_mutex_arc.clone();
     let remaining_cards_amount_arc_clone = remaining_cards_amount_arc.clone();
     let displayed_bank_cards_amount_mutex_arc_clone = displayed_bank_cards_amount_mutex_arc.clone();
     let basic_strategy_action_arc_mutex_clone = basic_strategy_action_mutex_arc.clone();
+    let rules_mutex_arc_clone = rules_mutex_arc.clone();
+    let offer_mutex_arc_clone = offer_mutex_arc.clone();
+    let insurance_result_mutex_arc_clone = insurance_result_mutex_arc.clone();
+    let round_results_mutex_arc_clone = round_results_mutex_arc.clone();
+    let bankroll_mutex_arc_clone = bankroll_mutex_arc.clone();
+    let seats_cards_mutex_arc_clone = seats_cards_mutex_arc.clone();
+    let seats_results_mutex_arc_clone = seats_results_mutex_arc.clone();
+    let playing_seat_mutex_arc_clone = playing_seat_mutex_arc.clone();
+    let lobby_tables_mutex_arc_clone = lobby_tables_mutex_arc.clone();
+    let lobby_message_mutex_arc_clone = lobby_message_mutex_arc.clone();
+    let session_token_mutex_arc_clone = session_token_mutex_arc.clone();
+    let countdown_mutex_arc_clone = countdown_mutex_arc.clone();
+    let error_mutex_arc_clone = error_mutex_arc.clone();
+    let legal_actions_mutex_arc_clone = legal_actions_mutex_arc.clone();
+    let player_name_clone = player_name.clone();
 
     /* the socket handling is performed into a dedicated thread,
-     * otherwise the program would just block here waiting for messages */
+     * otherwise the program would just block here waiting for messages;
+     * the thread reconnects to resume the session of the player if the connection drops
+     * while he sits at a table, the program is stopped otherwise */
     thread::spawn(move || {
 
         const SERVER_ADDRESS: &str = "ws://127.0.0.1:3000";
-        let _ = connect(SERVER_ADDRESS, |sender| {
-            Client {
-                player_cards_mutex_arc: player_cards_mutex_arc_clone.clone(),
-                bank_cards_mutex_arc: bank_cards_mutex_arc_clone.clone(),
-                player_points_mutex_arc: player_points_mutex_arc_clone.clone(),
-                bank_points_mutex_arc: bank_points_mutex_arc_clone.clone(),
-                cards_amount_arc: remaining_cards_amount_arc_clone.clone(),
-                displayed_bank_cards_amount_mutex_arc: displayed_bank_cards_amount_mutex_arc_clone.clone(),
-                basic_strategy_action_mutex_arc: basic_strategy_action_arc_mutex_clone.clone(),
-                socket_sender: sender,
-                channel_sender: channel_sender.clone(),
+        const MAX_RECONNECTION_ATTEMPTS: u32 = 30;
+        const RECONNECTION_INTERVAL: Duration = Duration::from_secs(2);
+
+        let mut reconnection_attempts = 0;
+
+        loop {
+
+            let connection_time = Instant::now();
+
+            let _ = connect(SERVER_ADDRESS, |sender| {
+                Client {
+                    player_cards_mutex_arc: player_cards_mutex_arc_clone.clone(),
+                    bank_cards_mutex_arc: bank_cards_mutex_arc_clone.clone(),
+                    player_points_mutex_arc: player_points_mutex_arc_clone.clone(),
+                    active_hand_mutex_arc: active_hand_mutex_arc_clone.clone(),
+                    bank_points_mutex_arc: bank_points_mutex_arc_clone.clone(),
+                    cards_amount_arc: remaining_cards_amount_arc_clone.clone(),
+                    displayed_bank_cards_amount_mutex_arc: displayed_bank_cards_amount_mutex_arc_clone.clone(),
+                    basic_strategy_action_mutex_arc: basic_strategy_action_arc_mutex_clone.clone(),
+                    rules_mutex_arc: rules_mutex_arc_clone.clone(),
+                    offer_mutex_arc: offer_mutex_arc_clone.clone(),
+                    insurance_result_mutex_arc: insurance_result_mutex_arc_clone.clone(),
+                    round_results_mutex_arc: round_results_mutex_arc_clone.clone(),
+                    bankroll_mutex_arc: bankroll_mutex_arc_clone.clone(),
+                    seats_cards_mutex_arc: seats_cards_mutex_arc_clone.clone(),
+                    seats_results_mutex_arc: seats_results_mutex_arc_clone.clone(),
+                    playing_seat_mutex_arc: playing_seat_mutex_arc_clone.clone(),
+                    lobby_tables_mutex_arc: lobby_tables_mutex_arc_clone.clone(),
+                    lobby_message_mutex_arc: lobby_message_mutex_arc_clone.clone(),
+                    session_token_mutex_arc: session_token_mutex_arc_clone.clone(),
+                    countdown_mutex_arc: countdown_mutex_arc_clone.clone(),
+                    error_mutex_arc: error_mutex_arc_clone.clone(),
+                    legal_actions_mutex_arc: legal_actions_mutex_arc_clone.clone(),
+                    player_name: player_name_clone.clone(),
+                    seat_index: None,
+                    resuming: false,
+                    shoe_verification: ShoeVerification::new(),
+                    socket_sender: sender,
+                    encoding: requested_encoding,
+                    channel_sender: channel_sender.clone(),
+                }
+            });
+
+            /* a connection lasting longer than the reconnection interval has been established,
+               the attempts are counted again from the next drop */
+            if connection_time.elapsed() > RECONNECTION_INTERVAL {
+                reconnection_attempts = 0;
+            }
+
+            let has_session = session_token_mutex_arc_clone.lock()
+                .unwrap()
+                .is_some();
+
+            if !has_session ||
+                reconnection_attempts >= MAX_RECONNECTION_ATTEMPTS {
+                channel_sender.send(Event::Disconnect).unwrap();
+                break;
             }
-        });
+
+            reconnection_attempts += 1;
+            thread::sleep(RECONNECTION_INTERVAL);
+        }
     });
 
     /* the program halts here until a concrete
        connection attempt status is established */
     let channel_message = channel_receiver.recv().unwrap();
 
-    let sender = match channel_message {
+    let mut sender = match channel_message {
         Event::Connect(s) => s,
-        _ => {
-            panic!("Unexpected channel message.");
+        Event::Disconnect => {
+            println!("Cannot connect to the server.");
+            return;
         }
     };
 
-    let new_player_message = SocketMessage {
-        action: MessageAction::Restart,
-        card_index: 0,
-        cards_amount: 0,
-        text: player_name.clone(),
-        player_handpoints: 0,
-        bank_cards: vec![],
-    };
-    let message = serde_json::to_string(&new_player_message).unwrap();
-    sender.send(message).unwrap();
-
     const WINDOW_WIDTH: f64 = 800.0;
     const WINDOW_HEIGHT: f64 = 600.0;
     let mut window: PistonWindow = WindowSettings::new(
@@ -208,12 +395,18 @@ fn main() {
     ).unwrap();
 
     let mut displayed_bank_cards_amount_last_update = Instant::now();
-    let mut last_player_action = MessageAction::Restart;
+    let mut last_player_action = PlayerAction::PlaceBet;
+    let mut bet_amount: u32 = 0;
+
+    const GREEN_COLOR: [f32; 4] = [0.2, 0.5, 0.3, 1.0];
 
     while let Some(event) = window.next() {
 
-        if Ok(Event::Disconnect) == channel_receiver.try_recv() {
-            break;
+        /* the new connection replaces the dropped one once the session is resumed */
+        match channel_receiver.try_recv() {
+            Ok(Event::Connect(new_sender)) => sender = new_sender,
+            Ok(Event::Disconnect) => break,
+            Err(_) => {},
         }
 
         let pressed_key = event.press_args();
@@ -222,18 +415,124 @@ fn main() {
             break;
         }
 
+        /* the last error is displayed for a few seconds, over the lobby and the table */
+        const ERROR_DISPLAY_DURATION: Duration = Duration::from_secs(5);
+        let error = error_mutex_arc.lock()
+            .unwrap()
+            .clone()
+            .filter(|(_, error_time)| error_time.elapsed() < ERROR_DISPLAY_DURATION)
+            .map(|(error, _)| error);
+
+        /* the lobby is displayed instead of the table until the player sits down */
+        let lobby_tables = lobby_tables_mutex_arc.lock()
+            .unwrap()
+            .clone();
+
+        if let Some(tables) = lobby_tables {
+
+            if let Some(Button::Keyboard(key)) = pressed_key {
+                send_lobby_request(
+                    &sender,
+                    key,
+                    &player_name,
+                    spectating,
+                );
+            }
+
+            let lobby_message = lobby_message_mutex_arc.lock()
+                .unwrap()
+                .clone();
+
+            window.draw_2d(
+                &event,
+                |context, window, device| {
+
+                    clear(
+                        GREEN_COLOR,
+                        window,
+                    );
+
+                    display_title(
+                        window,
+                        &context,
+                        &mut glyphs,
+                    );
+
+                    display_lobby(
+                        window,
+                        &context,
+                        &mut glyphs,
+                        &tables,
+                        &lobby_message,
+                        spectating,
+                    );
+
+                    if let Some(error) = &error {
+                        display_error(
+                            window,
+                            &context,
+                            &mut glyphs,
+                            error,
+                        );
+                    }
+
+                    glyphs.factory
+                        .encoder
+                        .flush(device);
+                }
+            );
+
+            continue;
+        }
+
+        /* the whole table is rebuilt from a snapshot of the server, in case the displayed table went out of sync */
+        if let Some(Button::Keyboard(Key::F5)) = pressed_key {
+            send_snapshot_request(&sender);
+        }
+
         let mut player_cards = player_cards_mutex_arc.lock().unwrap();
+        let active_hand = *active_hand_mutex_arc.lock().unwrap();
         let mut bank_cards = bank_cards_mutex_arc.lock().unwrap();
         let mut displayed_bank_cards_amount: MutexGuard<usize> =
             displayed_bank_cards_amount_mutex_arc.lock().unwrap();
-        let basic_strategy_action: MutexGuard<MessageAction> =
+        let basic_strategy_action: MutexGuard<PlayerAction> =
             basic_strategy_action_mutex_arc.lock().unwrap();
+        let mut round_results: MutexGuard<Vec<(HandResult, u32)>> =
+            round_results_mutex_arc.lock().unwrap();
+
+        /* once all the hands are finished,
+           the last hand remains the displayed one */
+        let displayed_hand = active_hand.min(
+            player_cards.len().saturating_sub(1)
+        );
+        let player_points = player_points_mutex_arc.lock()
+            .unwrap()
+            .get(displayed_hand)
+            .cloned()
+            .unwrap_or(0);
+
+        /* the bank hole card is only received when the bank cards
+           are revealed, the round results are sent by the server right after */
+        const BANK_CARDS_AMOUNT_DURING_ROUND: usize = 1;
+        let bank_cards_revealed = bank_cards.len() > BANK_CARDS_AMOUNT_DURING_ROUND;
+
+        /* the server prompts the actions the player can take, according to the table rules and his bankroll */
+        let legal_actions = legal_actions_mutex_arc.lock()
+            .unwrap()
+            .clone();
+        let betting = legal_actions.contains(&Action::PlaceBet);
+
+        /* the bet always respects the table limits, even before the rules are received */
+        let (min_bet, max_bet) = {
+            let rules = rules_mutex_arc.lock().unwrap();
+            (rules.min_bet, rules.max_bet)
+        };
+        bet_amount = bet_amount.clamp(min_bet, max_bet);
 
         const ANIMATED_DRAWING_INTERVAL: u64 = 2500;
-        const BANK_CARDS_MINIMUM_AMOUNT: usize = 1;
 
         if *displayed_bank_cards_amount < bank_cards.len() &&
-            bank_cards.len() != BANK_CARDS_MINIMUM_AMOUNT &&
+            bank_cards_revealed &&
             displayed_bank_cards_amount_last_update.elapsed() >
                 Duration::from_millis(ANIMATED_DRAWING_INTERVAL)
         {
@@ -241,77 +540,218 @@ fn main() {
             displayed_bank_cards_amount_last_update = Instant::now();
         }
 
+        /* the spectators only watch every seat of the table, they can only leave it */
+        if spectating {
+
+            if let Some(Button::Keyboard(Key::Backspace)) = pressed_key {
+                send_leave_request(&sender);
+            }
+
+            let seats_cards = seats_cards_mutex_arc.lock()
+                .unwrap()
+                .clone();
+            let seats_results = seats_results_mutex_arc.lock()
+                .unwrap()
+                .clone();
+            let playing_seat = *playing_seat_mutex_arc.lock().unwrap();
+
+            window.draw_2d(
+                &event,
+                |context, window, device| {
+
+                    clear(
+                        GREEN_COLOR,
+                        window,
+                    );
+
+                    display_title(
+                        window,
+                        &context,
+                        &mut glyphs,
+                    );
+
+                    if *displayed_bank_cards_amount == bank_cards.len() {
+
+                        display_bank_points(
+                            window,
+                            &context,
+                            &mut glyphs,
+                            &bank_points_mutex_arc,
+                        );
+                    }
+
+                    display_remaining_cards_amount(
+                        window,
+                        &context,
+                        &mut glyphs,
+                        &remaining_cards_amount_arc,
+                    );
+
+                    display_spectated_seats(
+                        window,
+                        &context,
+                        &mut glyphs,
+                        &cards_images,
+                        &seats_cards,
+                        &seats_results,
+                        playing_seat,
+                    );
+
+                    if !bank_cards.is_empty() {
+
+                        display_bank_cards(
+                            window,
+                            &context,
+                            &cards_images,
+                            &bank_cards,
+                            *displayed_bank_cards_amount,
+                            !bank_cards_revealed,
+                        );
+                    }
+
+                    if let Some(error) = &error {
+                        display_error(
+                            window,
+                            &context,
+                            &mut glyphs,
+                            error,
+                        );
+                    }
+
+                    glyphs.factory
+                        .encoder
+                        .flush(device);
+                }
+            );
+
+            continue;
+        }
+
         if let Some(Button::Keyboard(Key::Return)) = pressed_key {
 
             request_card(
                 &sender,
-                MessageAction::Hit,
-                &bank_points_mutex_arc,
-                &player_points_mutex_arc,
+                &legal_actions,
                 &mut player_cards,
                 &mut bank_cards,
+                &mut round_results,
                 &mut displayed_bank_cards_amount,
+                bet_amount,
             );
 
             displayed_bank_cards_amount_last_update = Instant::now();
-            last_player_action = MessageAction::Hit;
+            last_player_action = PlayerAction::Hit;
         }
 
         else if let Some(Button::Keyboard(Key::D)) = pressed_key {
 
-            const REQUIRED_CARDS_AMOUNT_FOR_DOUBLE: usize = 2;
-            if player_cards.len() == REQUIRED_CARDS_AMOUNT_FOR_DOUBLE {
-                request_card(
-                    &sender,
-                    MessageAction::DoubleDown,
-                    &bank_points_mutex_arc,
-                    &player_points_mutex_arc,
-                    &mut player_cards,
-                    &mut bank_cards,
-                    &mut displayed_bank_cards_amount,
-                );
+            if legal_actions.contains(&Action::DoubleDown) {
+
+                send_message(&sender, &ClientMessage::DoubleDown);
 
                 displayed_bank_cards_amount_last_update = Instant::now();
-                last_player_action = MessageAction::DoubleDown;
+                last_player_action = PlayerAction::DoubleDown;
             }
         }
 
         else if let Some(Button::Keyboard(Key::Space)) = pressed_key {
 
-            let bank_points = bank_points_mutex_arc.lock().unwrap();
-            let player_points = player_points_mutex_arc.lock().unwrap();
+            if legal_actions.contains(&Action::Stand) {
 
-            const BANK_MAX_HAND_POINTS: u8 = 17;
-            const PLAYER_MAX_HAND_POINTS: u8 = 21;
+                send_message(&sender, &ClientMessage::Stand);
 
-            if *bank_points < BANK_MAX_HAND_POINTS &&
-                *player_points <= PLAYER_MAX_HAND_POINTS {
+                displayed_bank_cards_amount_last_update = Instant::now();
+                last_player_action = PlayerAction::Stand;
+            }
+        }
 
-                let stand_message = SocketMessage {
-                    action: MessageAction::Stand,
-                    card_index: 0,
-                    cards_amount: 0,
-                    text: "".to_string(),
-                    player_handpoints: 0,
-                    bank_cards: vec![],
-                };
-                let message = serde_json::to_string(&stand_message).unwrap();
-                sender.send(message).unwrap();
+        else if let Some(Button::Keyboard(Key::Up)) = pressed_key {
 
-                displayed_bank_cards_amount_last_update = Instant::now();
-                last_player_action = MessageAction::Stand;
+            if betting {
+                bet_amount = (bet_amount + min_bet).min(max_bet);
+            }
+        }
+
+        else if let Some(Button::Keyboard(Key::Down)) = pressed_key {
+
+            if betting {
+                bet_amount = bet_amount.saturating_sub(min_bet).max(min_bet);
             }
         }
 
         else if let Some(Button::Keyboard(Key::S)) = pressed_key {
-            last_player_action = MessageAction::Split;
+
+            if legal_actions.contains(&Action::Split) {
+
+                send_message(&sender, &ClientMessage::Split);
+            }
+
+            last_player_action = PlayerAction::Split;
         }
 
+        else if let Some(Button::Keyboard(Key::Backspace)) = pressed_key {
+
+            /* the player can only leave between two rounds, not to lose his bet */
+            if betting {
+
+                send_leave_request(&sender);
+
+                last_player_action = PlayerAction::PlaceBet;
+            }
+        }
+
+        else if let Some(Button::Keyboard(key)) = pressed_key {
+
+            let mut offer: MutexGuard<Option<(Offer, u32)>> =
+                offer_mutex_arc.lock().unwrap();
+
+            /* the whole offered stake (half of the bet) is taken when the insurance is accepted,
+               any stake takes the even money,
+               the surrender key serves both the early and the late surrender */
+            let offer_message = match (*offer, key) {
+                (_, Key::R) if legal_actions.contains(&Action::Surrender) => Some(ClientMessage::Surrender),
+                (_, Key::N) if legal_actions.contains(&Action::DeclineSurrender) => Some(ClientMessage::DeclineSurrender),
+                (_, Key::N) if legal_actions.contains(&Action::DeclineInsurance) => Some(ClientMessage::DeclineInsurance),
+                (Some((_, stake)), Key::I) if legal_actions.contains(&Action::Insurance) => {
+                    Some(ClientMessage::Insurance { stake })
+                },
+                _ => None,
+            };
+
+            if let Some(offer_message) = offer_message {
+
+                send_message(&sender, &offer_message);
+
+                *offer = None;
+
+                if offer_message == ClientMessage::Surrender {
+                    last_player_action = PlayerAction::Surrender;
+                }
+            }
+        }
+
+        let offer = *offer_mutex_arc.lock().unwrap();
+        let bankroll = *bankroll_mutex_arc.lock().unwrap();
+        let seats_points: Vec<Vec<u8>> = seats_cards_mutex_arc.lock()
+            .unwrap()
+            .iter()
+            .map(|hands| get_hands_points(hands))
+            .collect();
+        let playing_seat = *playing_seat_mutex_arc.lock().unwrap();
+        let insurance_result = *insurance_result_mutex_arc.lock().unwrap();
+
+        /* rounded up, as the server does */
+        let countdown = countdown_mutex_arc.lock()
+            .unwrap()
+            .map(|deadline| {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                (remaining.as_millis() as u64).div_ceil(1000)
+            });
+
         window.draw_2d(
             &event,
             |context, mut window, device| {
 
-                const GREEN_COLOR: [f32; 4] = [0.2, 0.5, 0.3, 1.0];
                 clear(
                     GREEN_COLOR,
                     window,
@@ -327,7 +767,8 @@ fn main() {
                     window,
                     &context,
                     &mut glyphs,
-                    &player_points_mutex_arc,
+                    player_points,
+                    countdown,
                 );
 
                 if *displayed_bank_cards_amount == bank_cards.len() {
@@ -340,23 +781,34 @@ fn main() {
                     );
                 }
 
-                display_information(
+                /* nothing to play before the first round is dealt */
+                if !player_cards.is_empty() {
+
+                    display_information(
+                        window,
+                        &context,
+                        &mut glyphs,
+                        &round_results,
+                        &legal_actions,
+                        bank_cards.len(),
+                        *displayed_bank_cards_amount,
+                    );
+                }
+
+                display_basic_strategy_information(
                     window,
                     &context,
                     &mut glyphs,
-                    &player_points_mutex_arc,
-                    &bank_points_mutex_arc,
-                    player_cards.len(),
-                    bank_cards.len(),
-                    *displayed_bank_cards_amount,
+                    *basic_strategy_action,
+                    last_player_action,
                 );
 
-                display_basic_strategy_information(
+                display_offer_information(
                     window,
                     &context,
                     &mut glyphs,
-                    *basic_strategy_action,
-                    last_player_action,
+                    offer,
+                    insurance_result,
                 );
 
                 display_player_name(
@@ -366,6 +818,23 @@ fn main() {
                     &player_name,
                 );
 
+                display_bankroll(
+                    window,
+                    &context,
+                    &mut glyphs,
+                    bankroll,
+                    bet_amount,
+                    betting,
+                );
+
+                display_seats_information(
+                    window,
+                    &context,
+                    &mut glyphs,
+                    &seats_points,
+                    playing_seat,
+                );
+
                 display_remaining_cards_amount(
                     window,
                     &context,
@@ -379,15 +848,30 @@ fn main() {
 
                 const PLAYER_CARD_HORIZONTAL_POSITION: f64 = 300.0;
                 const PLAYER_CARD_VERTICAL_POSITION: f64 = 400.0;
-
-                display_player_cards(
-                    &mut window,
-                    &context,
-                    &cards_images,
-                    &player_cards,
-                    PLAYER_CARD_HORIZONTAL_POSITION,
-                    PLAYER_CARD_VERTICAL_POSITION,
-                );
+                const SPLIT_HANDS_DISTANCE: f64 = 120.0;
+                const ACTIVE_HAND_VERTICAL_OFFSET: f64 = 20.0;
+
+                /* the hands are displayed side by side after a split,
+                   the active hand is slightly raised */
+                for (hand_index, hand_cards) in player_cards.iter().enumerate() {
+
+                    let vertical_offset = if player_cards.len() > 1 &&
+                        hand_index == active_hand {
+                        ACTIVE_HAND_VERTICAL_OFFSET
+                    } else {
+                        0.0
+                    };
+
+                    display_player_cards(
+                        &mut window,
+                        &context,
+                        &cards_images,
+                        hand_cards,
+                        PLAYER_CARD_HORIZONTAL_POSITION +
+                            hand_index as f64 * SPLIT_HANDS_DISTANCE,
+                        PLAYER_CARD_VERTICAL_POSITION - vertical_offset,
+                    );
+                }
 
                 display_bank_cards(
                     &mut window,
@@ -395,8 +879,18 @@ fn main() {
                     &cards_images,
                     &bank_cards,
                     *displayed_bank_cards_amount,
+                    !bank_cards_revealed,
                 );
 
+                if let Some(error) = &error {
+                    display_error(
+                        window,
+                        &context,
+                        &mut glyphs,
+                        error,
+                    );
+                }
+
                 glyphs.factory
                     .encoder
                     .flush(device);                
@@ -0,0 +1,58 @@
+//! The output of the connection, sending the client messages with the encoding negotiated with the server.
+
+use ws::{
+    Message,
+    Sender,
+};
+
+use blackjack_protocol::{
+    ClientMessage,
+    Encoding,
+};
+
+/// Contains the web socket sender of the connection and the encoding accepted by the server.
+#[derive(PartialEq, Clone)]
+pub struct Output {
+    sender: Sender,
+    encoding: Encoding,
+}
+
+impl Output {
+
+    /// Creates the output of the connection.
+    ///
+    /// # Args:
+    ///
+    /// `sender` - the ws sender of the connection
+    /// `encoding` - the encoding negotiated during the handshake
+    pub fn new(
+        sender: Sender,
+        encoding: Encoding,
+    ) -> Output {
+        Output {
+            sender,
+            encoding,
+        }
+    }
+
+    /// Sends the given message, as a text frame in JSON or as a binary frame in MessagePack.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    pub fn send(
+        &self,
+        message: &ClientMessage,
+    ) {
+
+        let data = self.encoding.encode(message);
+
+        let message = if self.encoding.is_binary() {
+            Message::Binary(data)
+        } else {
+            Message::Text(String::from_utf8(data).unwrap())
+        };
+
+        self.sender.send(message).unwrap();
+    }
+}
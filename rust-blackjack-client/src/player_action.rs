@@ -0,0 +1,11 @@
+//! Player action enumeration, used to compare the actions of the player with the basic strategy.
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum PlayerAction {
+    PlaceBet,
+    Hit,
+    Stand,
+    DoubleDown,
+    Split,
+    Surrender,
+}
@@ -0,0 +1,196 @@
+//! The verification of the dealt cards against the commitment of the shoe published by the server.
+
+use blackjack_core::{
+    random_shuffle_order,
+    seed_commitment,
+    shoe_commitment,
+};
+
+/// Contains the commitment of the current shoe and every card received since then,
+/// with the amount of cards remaining into the shoe right after it has been drawn,
+/// which gives the position of the card into the shoe once the order is revealed.
+/// The hole card is only known once the bank cards are revealed, its position is kept until then.
+/// The seed sent by the player after a commitment is expected among the client seeds of the next shoe,
+/// whose seed has been committed at the same time.
+pub struct ShoeVerification {
+    commitment: Option<String>,
+    seed_commitment: Option<String>,
+    next_seed_commitment: Option<String>,
+    dealt_cards: Vec<(u16, u16)>,
+    hole_card_remaining_amount: Option<u16>,
+    sent_client_seed: Option<String>,
+    expected_client_seed: Option<String>,
+}
+
+impl ShoeVerification {
+
+    /// Creates a verification waiting for the commitment of a shoe.
+    pub fn new() -> ShoeVerification {
+        ShoeVerification {
+            commitment: None,
+            seed_commitment: None,
+            next_seed_commitment: None,
+            dealt_cards: vec![],
+            hole_card_remaining_amount: None,
+            sent_client_seed: None,
+            expected_client_seed: None,
+        }
+    }
+
+    /// Starts the verification of a new shoe, the cards received before are forgotten.
+    ///
+    /// # Args:
+    ///
+    /// `commitment` - the commitment of the order of the new shoe
+    /// `next_seed_commitment` - the commitment of the seed of the shoe following the new one
+    pub fn commit(
+        &mut self,
+        commitment: String,
+        next_seed_commitment: String,
+    ) {
+        self.commitment = Some(commitment);
+        self.seed_commitment = self.next_seed_commitment.replace(next_seed_commitment);
+        self.dealt_cards.clear();
+        self.hole_card_remaining_amount = None;
+        self.expected_client_seed = self.sent_client_seed.take();
+    }
+
+    /// Records the seed sent to the server, it has to be mixed into the shuffle of the next shoe.
+    ///
+    /// # Args:
+    ///
+    /// `client_seed` - the sent seed
+    pub fn record_client_seed(
+        &mut self,
+        client_seed: String,
+    ) {
+        self.sent_client_seed = Some(client_seed);
+    }
+
+    /// Records a received card, if the commitment of the shoe is known.
+    ///
+    /// # Args:
+    ///
+    /// `remaining_amount` - the amount of cards remaining into the shoe right after the card has been drawn
+    /// `wire_id` - the wire id of the card
+    pub fn record_card(
+        &mut self,
+        remaining_amount: u16,
+        wire_id: u16,
+    ) {
+
+        if self.commitment.is_some() {
+            self.dealt_cards.push((remaining_amount, wire_id));
+        }
+    }
+
+    /// Records the position of the withheld hole card, if the commitment of the shoe is known.
+    ///
+    /// # Args:
+    ///
+    /// `remaining_amount` - the amount of cards remaining into the shoe right after the hole card has been drawn
+    pub fn record_hole_card(
+        &mut self,
+        remaining_amount: u16,
+    ) {
+
+        if self.commitment.is_some() {
+            self.hole_card_remaining_amount = Some(remaining_amount);
+        }
+    }
+
+    /// Records the hole card once the bank cards are revealed, the hole card is the second bank card.
+    ///
+    /// # Args:
+    ///
+    /// `bank_cards` - the wire ids of all the bank cards
+    pub fn reveal_hole_card(
+        &mut self,
+        bank_cards: &[u16],
+    ) {
+
+        const HOLE_CARD_INDEX: usize = 1;
+
+        if let (Some(remaining_amount), Some(wire_id)) = (
+            self.hole_card_remaining_amount.take(),
+            bank_cards.get(HOLE_CARD_INDEX),
+        ) {
+            self.record_card(remaining_amount, *wire_id);
+        }
+    }
+
+    /// Checks the revealed order and server seed match the commitment, every received card
+    /// has been drawn at its place into the order, the shoe seed matches the commitment published
+    /// before the players sent their seeds, the seed of the player has been mixed into the shuffle
+    /// and, if the strategy of the table allows it, the order is the shuffle of the revealed seeds.
+    /// Returns the amount of verified cards, or the reason why the shoe cannot be trusted.
+    /// The verification of the next shoe starts with the next commitment.
+    ///
+    /// # Args:
+    ///
+    /// `server_seed` - the revealed secret seed of the server
+    /// `order` - the revealed wire ids of the cards of the shoe, starting with the burnt card
+    /// `shoe_seed` - the revealed seed of the shoe
+    /// `seed_salt` - the revealed salt of the commitment of the shoe seed
+    /// `client_seeds` - the revealed seeds of the players mixed into the shuffle
+    /// `recomputable` - true if the order can be recomputed from the seeds
+    pub fn verify(
+        &mut self,
+        server_seed: &str,
+        order: &[u16],
+        shoe_seed: u64,
+        seed_salt: &str,
+        client_seeds: &[String],
+        recomputable: bool,
+    ) -> Result<usize, &'static str> {
+
+        self.hole_card_remaining_amount = None;
+        let expected_client_seed = self.expected_client_seed.take();
+        let expected_seed_commitment = self.seed_commitment.take();
+
+        let commitment = match self.commitment.take() {
+            Some(commitment) => commitment,
+            None => return Err("no commitment has been received for this shoe"),
+        };
+
+        if shoe_commitment(order, server_seed) != commitment {
+            return Err("the revealed order does not match the commitment");
+        }
+
+        let dealt_cards: Vec<(u16, u16)> = self.dealt_cards.drain(..).collect();
+
+        /* the wire ids are compared, so two copies of the same card cannot be swapped */
+        for (remaining_amount, wire_id) in &dealt_cards {
+
+            let committed_wire_id = order.len()
+                .checked_sub(*remaining_amount as usize + 1)
+                .and_then(|position| order.get(position));
+
+            if committed_wire_id != Some(wire_id) {
+                return Err("a received card is not the committed one");
+            }
+        }
+
+        /* the seed of the first shoe watched by the player has been committed before he arrived */
+        if let Some(expected_seed_commitment) = expected_seed_commitment {
+
+            if seed_commitment(shoe_seed, seed_salt) != expected_seed_commitment {
+                return Err("the revealed shoe seed does not match its commitment");
+            }
+        }
+
+        if let Some(client_seed) = expected_client_seed {
+
+            if !client_seeds.contains(&client_seed) {
+                return Err("the seed of the player has not been mixed into the shuffle");
+            }
+        }
+
+        if recomputable &&
+            random_shuffle_order(order, shoe_seed, client_seeds) != order {
+            return Err("the revealed order is not the shuffle of the revealed seeds");
+        }
+
+        Ok(dealt_cards.len())
+    }
+}
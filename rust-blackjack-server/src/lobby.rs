@@ -0,0 +1,251 @@
+//! The lobby of the server, hosting all the tables the players can choose from.
+
+use ws::Sender;
+use ws::util::Token;
+
+use blackjack_core::{
+    ShuffleStrategy,
+    TableRules,
+    TableSummary,
+};
+
+use crate::output::Output;
+use crate::table::Table;
+
+/// Contains all the tables of the server, each one with its own rules.
+/// The tables are identified by their position into the list, they are never removed
+/// so a table index remains valid for the whole server lifetime.
+/// Every player joining the server gets a new identifier, never reused.
+/// The timers of all the tables are checked periodically by one single timeout, scheduled on the oldest open connection
+/// and moved to the next one when it closes. The timeout token changes every time the check is moved,
+/// so a timeout scheduled on a closed connection is ignored. The check stops while no connection is open,
+/// nobody can play then, and it catches up as soon as a connection opens.
+pub struct Lobby {
+    tables: Vec<Table>,
+    next_player_id: u32,
+    connections: Vec<Sender>,
+    timers_token: Token,
+}
+
+impl Lobby {
+
+    /// Creates a new lobby without any table.
+    pub fn new() -> Lobby {
+        Lobby {
+            tables: vec![],
+            next_player_id: 1,
+            connections: vec![],
+            timers_token: Token(0),
+        }
+    }
+
+    /// Checks the name of a new player and gives him an identifier.
+    /// Returns the identifier and the trimmed name, or the reason why the name is refused.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the player
+    pub fn register_player(
+        &mut self,
+        name: &str,
+    ) -> Result<(u32, String), &'static str> {
+
+        const MAX_PLAYER_NAME_LENGTH: usize = 20;
+
+        let name = name.trim().to_string();
+
+        if name.is_empty() ||
+            name.chars().count() > MAX_PLAYER_NAME_LENGTH {
+            return Err("the player name must contain between 1 and 20 characters");
+        }
+
+        if name.chars().any(char::is_control) {
+            return Err("the player name cannot contain control characters");
+        }
+
+        let player_id = self.next_player_id;
+        self.next_player_id += 1;
+
+        Ok((player_id, name))
+    }
+
+    /// Opens a new table with the given name and rules, returns the index of the table,
+    /// or the reason why the table cannot be opened.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the table, displayed into the lobby
+    /// `rules` - the rules of the table
+    /// `table_seed` - the seed every shoe seed of the table is derived from, a random one is used if none is given
+    /// `replayed_client_seeds` - the logged client seeds of the first shoes of the table, to deal them again
+    /// `shuffle_strategy` - the way the shoe of the table is shuffled
+    pub fn create_table(
+        &mut self,
+        name: String,
+        rules: TableRules,
+        table_seed: Option<u64>,
+        replayed_client_seeds: Vec<Vec<String>>,
+        shuffle_strategy: Box<dyn ShuffleStrategy>,
+    ) -> Result<usize, &'static str> {
+
+        const MAX_TABLES_AMOUNT: usize = 32;
+        const MAX_TABLE_NAME_LENGTH: usize = 32;
+
+        let name = name.trim().to_string();
+
+        if name.is_empty() ||
+            name.chars().count() > MAX_TABLE_NAME_LENGTH {
+            return Err("the table name must contain between 1 and 32 characters");
+        }
+
+        if self.tables.len() >= MAX_TABLES_AMOUNT {
+            return Err("the maximum amount of tables is reached");
+        }
+
+        rules.validate()?;
+
+        self.tables.push(
+            Table::new(
+                name,
+                rules,
+                table_seed,
+                replayed_client_seeds,
+                shuffle_strategy,
+            )
+        );
+
+        Ok(self.tables.len() - 1)
+    }
+
+    /// Returns the summaries of all the tables, in the order of their indices.
+    pub fn summaries(&self) -> Vec<TableSummary> {
+        self.tables
+            .iter()
+            .map(Table::summary)
+            .collect()
+    }
+
+    /// Seats back the player holding the given session token at his table.
+    /// Returns the indices of the table and of the seat, or nothing if the session does not exist (anymore).
+    ///
+    /// # Args:
+    ///
+    /// `session_token` - the session token received by the player when he sat down
+    /// `output` - the player new output in order to send him information
+    pub fn resume(
+        &mut self,
+        session_token: &str,
+        output: Output,
+    ) -> Option<(usize, usize)> {
+
+        self.tables
+            .iter_mut()
+            .enumerate()
+            .find_map(|(table_index, table)| {
+                table.resume(session_token, output.clone())
+                    .map(|seat_index| (table_index, seat_index))
+            })
+    }
+
+    /// Registers a new open connection, the timers are checked at once as no connection checked them
+    /// while nobody was connected. The first connection starts the periodic check.
+    ///
+    /// # Args:
+    ///
+    /// `output` - the ws sender of the connection
+    pub fn open_connection(
+        &mut self,
+        output: Sender,
+    ) {
+
+        self.check_timers();
+        self.connections.push(output);
+
+        if self.connections.len() == 1 {
+            self.schedule_timers_check();
+        }
+    }
+
+    /// Unregisters a closed connection, the periodic check moves to the next connection
+    /// if the closed one was scheduling it.
+    ///
+    /// # Args:
+    ///
+    /// `connection_id` - the identifier of the closed connection
+    pub fn close_connection(
+        &mut self,
+        connection_id: u32,
+    ) {
+
+        let position = self.connections
+            .iter()
+            .position(|output| output.connection_id() == connection_id);
+
+        if let Some(position) = position {
+
+            self.connections.remove(position);
+
+            if position == 0 && !self.connections.is_empty() {
+                self.schedule_timers_check();
+            }
+        }
+    }
+
+    /// Handles a triggered timeout of a connection: checks the timers and schedules the next check
+    /// if the timeout is the current periodic check, ignores it otherwise.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the token of the triggered timeout
+    pub fn trigger_timeout(
+        &mut self,
+        event: Token,
+    ) {
+
+        if event != self.timers_token {
+            return;
+        }
+
+        self.check_timers();
+        self.schedule_timers_check();
+    }
+
+    /// Schedules the next periodic check of the timers on the oldest open connection, with a new token.
+    fn schedule_timers_check(&mut self) {
+
+        const TIMERS_CHECK_INTERVAL: u64 = 1000;
+
+        self.timers_token = Token(self.timers_token.0.wrapping_add(1));
+
+        let output = match self.connections.first() {
+            Some(output) => output,
+            None => return,
+        };
+
+        if let Err(error) = output.timeout(TIMERS_CHECK_INTERVAL, self.timers_token) {
+            println!("Cannot schedule the timers check on the connection {}: {}.", output.connection_id(), error);
+        }
+    }
+
+    /// Frees the seats of the players disconnected for longer than the grace period
+    /// and takes the default actions of the expired decisions, at every table.
+    pub fn check_timers(&mut self) {
+
+        for table in &mut self.tables {
+            table.expire_sessions();
+            table.expire_decision();
+        }
+    }
+
+    /// Returns the table at the given index, if it exists.
+    ///
+    /// # Args:
+    ///
+    /// `table_index` - the index of the table
+    pub fn table_mut(
+        &mut self,
+        table_index: usize,
+    ) -> Option<&mut Table> {
+        self.tables.get_mut(table_index)
+    }
+}
@@ -4,20 +4,182 @@ extern crate ws;
 extern crate rand;
 extern crate serde;
 extern crate serde_json;
+extern crate sha2;
+extern crate blackjack_core;
+extern crate blackjack_protocol;
 #[macro_use] extern crate serde_derive;
 
-mod message_action;
-mod socket_message;
+mod output;
+mod seat;
+mod round_phase;
+mod table;
+mod lobby;
 mod server;
 
-use ws::listen;
+use ws::{
+    Builder,
+    Settings,
+};
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::rc::Rc;
+
+use blackjack_core::{
+    ContinuousShuffle,
+    HandShuffle,
+    RandomShuffle,
+    Rank,
+    ScriptedShuffle,
+    ShuffleStrategy,
+    Suit,
+    TableRules,
+};
 
 use server::Server;
+use lobby::Lobby;
+
+/// The shuffle strategy of a table, as described into the tables file.
+/// The scripted order lists the rank and the suit of the first cards to deal after every shuffle.
+#[derive(Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ShuffleConfig {
+    #[default]
+    Random,
+    Continuous,
+    Hand,
+    Scripted {
+        cards: Vec<(Rank, Suit)>,
+    },
+}
+
+impl ShuffleConfig {
+
+    /// Returns the shuffle strategy described by the configuration.
+    fn into_strategy(self) -> Box<dyn ShuffleStrategy> {
+        match self {
+            ShuffleConfig::Random => Box::new(RandomShuffle),
+            ShuffleConfig::Continuous => Box::new(ContinuousShuffle),
+            ShuffleConfig::Hand => Box::new(HandShuffle),
+            ShuffleConfig::Scripted { cards } => Box::new(ScriptedShuffle { cards }),
+        }
+    }
+}
+
+/// One table opened when the server starts, as described into the tables file.
+/// Any rule missing from the table description takes its default value.
+/// The seed of the table is optional, in order to deal again the cards of the logged shoes,
+/// with the logged client seeds of every shoe (starting with the first one) if the players sent some,
+/// the shoe is shuffled at random if no shuffle strategy is given.
+#[derive(Deserialize)]
+struct TableConfig {
+    name: String,
+    #[serde(default)]
+    rules: TableRules,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    client_seeds: Vec<Vec<String>>,
+    #[serde(default)]
+    shuffle: ShuffleConfig,
+}
+
+/// Loads the tables descriptions from the given JSON file, stops the program if the file cannot be used.
+///
+/// # Args:
+///
+/// `file_path` - the path of the tables file
+fn load_tables(file_path: &str) -> Vec<TableConfig> {
+
+    let content = fs::read_to_string(file_path)
+        .expect("Cannot read the tables file.");
+
+    serde_json::from_str(&content)
+        .expect("Cannot parse the tables file.")
+}
 
 fn main() {
 
+    const SEED_OPTION: &str = "--seed";
+
+    let mut tables_file_path = None;
+    let mut seed: Option<u64> = None;
+
+    let mut arguments = env::args().skip(1);
+
+    while let Some(argument) = arguments.next() {
+
+        if argument == SEED_OPTION {
+            seed = Some(
+                arguments.next()
+                    .and_then(|value| value.parse().ok())
+                    .expect("The seed must be an unsigned integer.")
+            );
+        } else {
+            tables_file_path = Some(argument);
+        }
+    }
+
+    /* the tables file is optional,
+       one table with the default rules is opened if no file is given */
+    let tables = match tables_file_path {
+        Some(file_path) => load_tables(&file_path),
+        None => vec![
+            TableConfig {
+                name: "Main table".to_string(),
+                rules: TableRules::default(),
+                seed: None,
+                client_seeds: vec![],
+                shuffle: ShuffleConfig::Random,
+            }
+        ],
+    };
+
+    let mut lobby = Lobby::new();
+
+    for (table_index, table) in tables.into_iter().enumerate() {
+
+        /* the seed of the command line is shifted for every table,
+           so the tables do not all deal the same cards */
+        let table_seed = table.seed.or_else(|| {
+            seed.map(|seed| seed.wrapping_add(table_index as u64))
+        });
+
+        let created_table = lobby.create_table(
+            table.name,
+            table.rules,
+            table_seed,
+            table.client_seeds,
+            table.shuffle.into_strategy(),
+        );
+
+        if let Err(error) = created_table {
+            panic!("Invalid tables file: {}.", error);
+        }
+    }
+
+    /* all the connected players share the same lobby */
+    let lobby = Rc::new(RefCell::new(lobby));
+
+    /* every connection can be sent a few dozen messages by one action ending a round,
+       the messages are queued until the action has been handled */
+    const QUEUE_SIZE: usize = 64;
+    let settings = Settings {
+        queue_size: QUEUE_SIZE,
+        ..Settings::default()
+    };
+
     const LISTENING_ADDRESS: &str = "127.0.0.1:3000";
-    listen(LISTENING_ADDRESS, |output| {
-        Server::new(output)
-    }).unwrap();
+    Builder::new()
+        .with_settings(settings)
+        .build(|output| {
+            Server::new(
+                output,
+                lobby.clone(),
+            )
+        })
+        .unwrap()
+        .listen(LISTENING_ADDRESS)
+        .unwrap();
 }
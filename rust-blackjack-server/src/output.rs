@@ -0,0 +1,84 @@
+//! The output of one connection, sending the server messages with the encoding negotiated by the client.
+
+use ws::{
+    CloseCode,
+    Message,
+    Sender,
+};
+
+use blackjack_protocol::{
+    Encoding,
+    ServerMessage,
+};
+
+/// Contains the web socket output sender of a player or a spectator and the encoding of his connection.
+#[derive(Clone)]
+pub struct Output {
+    sender: Sender,
+    encoding: Encoding,
+}
+
+impl Output {
+
+    /// Creates the output of a connection.
+    ///
+    /// # Args:
+    ///
+    /// `sender` - the ws sender of the connection
+    /// `encoding` - the encoding negotiated during the handshake
+    pub fn new(
+        sender: Sender,
+        encoding: Encoding,
+    ) -> Output {
+        Output {
+            sender,
+            encoding,
+        }
+    }
+
+    /// Returns the identifier of the connection.
+    pub fn connection_id(&self) -> u32 {
+        self.sender.connection_id()
+    }
+
+    /// Closes the connection, when another connection takes it over.
+    pub fn close(&self) {
+
+        if let Err(error) = self.sender.close(CloseCode::Normal) {
+            println!(
+                "Cannot close the connection {}: {}.",
+                self.connection_id(),
+                error,
+            );
+        }
+    }
+
+    /// Sends the given message, as a text frame in JSON or as a binary frame in MessagePack.
+    /// A message which cannot be queued (the queue of the connections is full) is logged and dropped,
+    /// the other connections must not go down with it.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    pub fn send(
+        &self,
+        message: &ServerMessage,
+    ) {
+
+        let data = self.encoding.encode(message);
+
+        let message = if self.encoding.is_binary() {
+            Message::Binary(data)
+        } else {
+            Message::Text(String::from_utf8(data).unwrap())
+        };
+
+        if let Err(error) = self.sender.send(message) {
+            println!(
+                "Cannot send a message to the connection {}: {}.",
+                self.connection_id(),
+                error,
+            );
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! The round phase enumeration, the table goes through all the phases in order during one round.
+
+use blackjack_protocol::TablePhase;
+
+/// The current phase of the round of a table. Only the actions of the current phase are accepted,
+/// the hands are played seat by seat during the players turns.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum RoundPhase {
+    Betting,
+    Dealing,
+    EarlySurrender,
+    Insurance,
+    PlayerTurn(usize),
+    DealerTurn,
+    Settlement,
+}
+
+impl RoundPhase {
+
+    /// Returns the phase sent to the clients into the table snapshots, without the index of the playing seat.
+    pub fn table_phase(self) -> TablePhase {
+        match self {
+            RoundPhase::Betting => TablePhase::Betting,
+            RoundPhase::Dealing => TablePhase::Dealing,
+            RoundPhase::EarlySurrender => TablePhase::EarlySurrender,
+            RoundPhase::Insurance => TablePhase::Insurance,
+            RoundPhase::PlayerTurn(_) => TablePhase::PlayerTurn,
+            RoundPhase::DealerTurn => TablePhase::DealerTurn,
+            RoundPhase::Settlement => TablePhase::Settlement,
+        }
+    }
+}
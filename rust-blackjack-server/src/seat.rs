@@ -0,0 +1,156 @@
+//! One seat of the table, occupied by one connected player.
+
+use std::time::Instant;
+
+use blackjack_core::{
+    Card,
+    Hand,
+};
+use blackjack_protocol::SeatSnapshot;
+
+use crate::output::Output;
+
+/// Contains the output of the seated player, his identifier and name, his session token, his bankroll,
+/// his bet for the next round and his hands during the round.
+/// The seat is kept for a while after the player got disconnected, he can take it back with his session token.
+/// A player who did not bet in time sits out the next rounds until he bets again.
+/// The last seed sent by the player is mixed into the shuffle of the next shoe.
+/// The player holds more than one hand after a split, the hands are played in order,
+/// each one with its own bet (doubled if the player doubled down on it).
+/// A player taking even money on his natural is paid immediately, his hand is settled before the end of the round.
+pub struct Seat {
+    pub output: Output,
+    pub player_id: u32,
+    pub name: String,
+    pub session_token: String,
+    pub disconnection_time: Option<Instant>,
+    pub client_seed: Option<String>,
+    pub bankroll: u32,
+    pub bet: u32,
+    pub sitting_out: bool,
+    pub in_round: bool,
+    pub hands: Vec<Hand>,
+    pub hand_bets: Vec<u32>,
+    pub active_hand: usize,
+    pub insurance_open: bool,
+    pub insurance_stake: u32,
+    pub surrender_open: bool,
+    pub surrendered: bool,
+    pub settled: bool,
+}
+
+impl Seat {
+
+    /// Creates a new seat for a player who did not bet yet.
+    ///
+    /// # Args:
+    ///
+    /// `output` - the player output in order to send him information
+    /// `player_id` - the identifier of the player on the server
+    /// `name` - the name of the player
+    /// `session_token` - the secret token the player resumes his session with after a disconnection
+    /// `bankroll` - the starting bankroll of the player
+    pub fn new(
+        output: Output,
+        player_id: u32,
+        name: String,
+        session_token: String,
+        bankroll: u32,
+    ) -> Seat {
+        Seat {
+            output,
+            player_id,
+            name,
+            session_token,
+            disconnection_time: None,
+            client_seed: None,
+            bankroll,
+            bet: 0,
+            sitting_out: false,
+            in_round: false,
+            hands: vec![],
+            hand_bets: vec![],
+            active_hand: 0,
+            insurance_open: false,
+            insurance_stake: 0,
+            surrender_open: false,
+            surrendered: false,
+            settled: false,
+        }
+    }
+
+    /// Prepares the seat for a new round played for the placed bet, with one empty hand.
+    pub fn start_round(&mut self) {
+        self.in_round = true;
+        self.hands = vec![Hand::new()];
+        self.hand_bets = vec![self.bet];
+        self.active_hand = 0;
+        self.insurance_stake = 0;
+        self.surrendered = false;
+        self.settled = false;
+    }
+
+    /// Returns all the cards of the hands of the seat, in order to discard them.
+    pub fn cards(&self) -> Vec<Card> {
+        self.hands
+            .iter()
+            .flat_map(|hand| hand.cards().to_vec())
+            .collect()
+    }
+
+    /// Indicates if the player has a natural blackjack, only possible with the two first cards of his first hand.
+    pub fn has_blackjack(&self) -> bool {
+        self.hands
+            .first()
+            .is_some_and(|hand| hand.is_blackjack())
+    }
+
+    /// Indicates if the player is currently connected, the messages are not sent to a disconnected player.
+    pub fn is_connected(&self) -> bool {
+        self.disconnection_time.is_none()
+    }
+
+    /// Indicates if the player still has a hand to play.
+    pub fn is_playing(&self) -> bool {
+        self.in_round &&
+            self.active_hand < self.hands.len()
+    }
+
+    /// Returns the public state of the seat, sent into the table snapshots.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    pub fn snapshot(
+        &self,
+        seat_index: usize,
+    ) -> SeatSnapshot {
+        SeatSnapshot {
+            seat_index: seat_index as u8,
+            name: self.name.clone(),
+            bankroll: self.bankroll,
+            bet: self.bet,
+            hands: self.hands
+                .iter()
+                .map(|hand| {
+                    hand.cards()
+                        .iter()
+                        .map(|card| card.wire_id())
+                        .collect()
+                })
+                .collect(),
+            hands_points: self.hands
+                .iter()
+                .map(Hand::soft_total)
+                .collect(),
+            active_hand: self.active_hand as u8,
+            connected: self.is_connected(),
+        }
+    }
+
+    /// Indicates if the player is still waiting for an insurance or early surrender decision.
+    pub fn is_deciding(&self) -> bool {
+        self.insurance_open ||
+            self.surrender_open
+    }
+}
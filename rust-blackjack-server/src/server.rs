@@ -1,4 +1,4 @@
-//! The main server structure and implementation.
+//! The server structure handling the connection of one player to the lobby and to his table.
 
 use ws::{
     Sender,
@@ -7,196 +7,454 @@ use ws::{
     CloseCode,
     Handshake,
     Message,
+    Request,
+    Response,
 };
+use ws::util::Token;
 
-use rand::thread_rng;
-use rand::seq::SliceRandom;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-use crate::socket_message::SocketMessage;
-use crate::message_action::MessageAction;
+use blackjack_core::{
+    RandomShuffle,
+    TableRules,
+};
+use blackjack_protocol::{
+    ClientMessage,
+    Encoding,
+    ErrorCode,
+    ProtocolVersion,
+    ServerMessage,
+    PROTOCOL_VERSION,
+};
 
-/// Contains the web socket output sender and the cards array.
-///
-/// NOTE: there are many more optimized ways to store the cards (memory and time complexity), but
-/// we voluntarily keep a raw array to store them all in order to create a genuine black-jack game situation
+use crate::lobby::Lobby;
+use crate::output::Output;
+use crate::table::Rejection;
+
+/// Contains the web socket output sender of one player, the encoding of his connection, the lobby shared by all the players
+/// and the table and seat of the player, once he joined a table, or the table he watches as a spectator.
+/// The player cannot do anything before joining the server with a valid name and a compatible protocol version,
+/// he is then identified by his player identifier and his name.
+/// All the connections are handled by the same thread, so the lobby is only shared by reference counting.
+/// The lobby periodically frees the seats of the players disconnected for too long,
+/// as the closed connections cannot do it themselves, and plays the expired decisions,
+/// through a timeout it schedules on one of the open connections.
 pub struct Server {
     output: Sender,
-    cards: Vec<u16>,
-    players_handpoints: Vec<u8>,
-    bank_handpoints: u8,
-    bank_cards: Vec<u8>,
+    encoding: Encoding,
+    lobby: Rc<RefCell<Lobby>>,
+    player: Option<(u32, String)>,
+    seat: Option<(usize, usize)>,
+    watched_table: Option<usize>,
 }
 
-/// Return card points according to a given index.
-///
-/// Args:
-///
-/// `card_index` - the current card index,
-/// `player_handpoints` - the current player handpoints amount,
-fn get_card_points(
-    card_index: u8,
-    player_handpoints: u8,
-) -> u8 {
-
-    const TEN_POINTS_CARDS_START_INDEX: u8 = 32;
-    const ACE_CARDS_START_INDEX: u8 = 48;
-
-    if card_index >= TEN_POINTS_CARDS_START_INDEX &&
-        card_index < ACE_CARDS_START_INDEX {
-
-        const TEN_VALUE_CARDS_POINTS_AMOUNT: u8 = 10;
-        return TEN_VALUE_CARDS_POINTS_AMOUNT;
+impl Server {
+
+    /// Creates a new server for a new connection, the player stays into the lobby until he joins a table.
+    /// The messages are encoded in JSON until the handshake selects another encoding.
+    ///
+    /// # Args:
+    ///
+    /// `output` - the server ws sender in order to send back information
+    /// `lobby` - the lobby shared by all the players
+    pub fn new(
+        output: ws::Sender,
+        lobby: Rc<RefCell<Lobby>>,
+    ) -> Server {
+        Server {
+            output,
+            encoding: Encoding::Json,
+            lobby,
+            player: None,
+            seat: None,
+            watched_table: None,
+        }
     }
-    else if card_index >= ACE_CARDS_START_INDEX {
 
-        const ACE_CARDS_FIRST_POINTS_AMOUNT: u8 = 1;
-        const ACE_CARDS_SECOND_POINTS_AMOUNT: u8 = 11;
-        const MAX_HAND_POINTS_FOR_ACE_CARDS_SECOND_POINTS_AMOUNT: u8 = 11;
+    /// Returns the name of the player for the logs, or the connection identifier if the player has not joined yet.
+    fn player_label(&self) -> String {
+        match self.player {
+            Some((_, ref name)) => name.clone(),
+            None => format!("the connection {}", self.output.connection_id()),
+        }
+    }
 
-        /* FIXME: the player should also be able to
-           select an ace value in some situations */
+    /// Returns the output of the connection, given to the tables so they send the messages with its encoding.
+    fn table_output(&self) -> Output {
+        Output::new(
+            self.output.clone(),
+            self.encoding,
+        )
+    }
 
-        if player_handpoints >= MAX_HAND_POINTS_FOR_ACE_CARDS_SECOND_POINTS_AMOUNT {
-            return ACE_CARDS_FIRST_POINTS_AMOUNT;
-        }
+    /// Sends the given message to the player.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    fn send(
+        &self,
+        message: &ServerMessage,
+    ) {
+        self.table_output().send(message);
+    }
 
-        return ACE_CARDS_SECOND_POINTS_AMOUNT;
+    /// Sends the given error to the player and logs it, the rejected message is ignored.
+    ///
+    /// # Args:
+    ///
+    /// `code` - the code of the error
+    /// `reason` - the description of the error
+    fn send_error(
+        &self,
+        code: ErrorCode,
+        reason: &str,
+    ) {
 
-    } else {
+        println!(
+            "Reject a message from {}: {:?}, {}.",
+            self.player_label(),
+            code,
+            reason,
+        );
 
-        const CARDS_WITH_SAME_VALUE_BY_COLOR: u8 = 4;
-        const MINIMUM_CARD_VALUE: u8 = 2;
-        return card_index / CARDS_WITH_SAME_VALUE_BY_COLOR
-            + MINIMUM_CARD_VALUE;
+        self.send(&ServerMessage::Error {
+            code,
+            message: reason.to_string(),
+        });
     }
-}
 
-impl Server {
+    /// Sends the summaries of all the tables to the player, with an optional reason
+    /// if his last lobby request failed.
+    ///
+    /// # Args:
+    ///
+    /// `error` - the reason of the failure of the last request, if it failed
+    fn send_tables(
+        &self,
+        error: Option<&str>,
+    ) {
+
+        let tables_message = ServerMessage::Tables {
+            tables: self.lobby.borrow().summaries(),
+            error: error.map(|error| error.to_string()),
+        };
+
+        self.send(&tables_message);
+    }
 
-    /// Creates a new server and shuffles all the cards, making them ready to be drawn.
+    /// Answers the join request of the player: the connection is closed if the protocol version of the player
+    /// is not compatible with the server one, the request is rejected if the name is not valid,
+    /// otherwise the player gets an identifier and receives the tables.
     ///
     /// # Args:
     ///
-    /// `output` - the server ws sender in order to send back information
-    pub fn new(output: ws::Sender) -> Server {
+    /// `name` - the name of the player
+    /// `version` - the protocol version of the player
+    fn greet(
+        &mut self,
+        name: &str,
+        version: ProtocolVersion,
+    ) -> std::result::Result<(), Rejection> {
+
+        if !PROTOCOL_VERSION.is_compatible_with(&version) {
 
-        const MIN_CARD_ID: u16 = 0;
-        const MAX_CARD_ID: u16 = 416;
+            println!(
+                "Reject the protocol version {}, the server speaks {}.",
+                version,
+                PROTOCOL_VERSION,
+            );
 
-        let mut all_cards: Vec<u16> = (MIN_CARD_ID..MAX_CARD_ID).collect();
-        let cards: &mut [u16] = &mut all_cards;
+            self.send(&ServerMessage::IncompatibleVersion { version: PROTOCOL_VERSION });
+            self.output.close(CloseCode::Protocol).unwrap();
+            return Ok(());
+        }
 
-        let mut rng = thread_rng();
-        cards.shuffle(&mut rng);
+        let (player_id, name) = self.lobby
+            .borrow_mut()
+            .register_player(name)
+            .map_err(|reason| (ErrorCode::InvalidName, reason))?;
 
-        Server {
-            output: output,
-            cards: all_cards,
-            players_handpoints: vec![],
-            bank_handpoints: 0,
-            bank_cards: vec![],
+        println!(
+            "{} joins the server from the connection {} as the player {}.",
+            name,
+            self.output.connection_id(),
+            player_id,
+        );
+
+        self.player = Some((player_id, name));
+
+        self.send(&ServerMessage::Hello { version: PROTOCOL_VERSION });
+        self.send_tables(None);
+
+        Ok(())
+    }
+
+    /// Seats the player at the given table, the tables are sent again with the reason
+    /// if the player cannot sit down.
+    ///
+    /// # Args:
+    ///
+    /// `table_index` - the index of the table to join
+    fn join_table(
+        &mut self,
+        table_index: usize,
+    ) {
+
+        let (player_id, name) = self.player
+            .clone()
+            .unwrap();
+
+        let joined_seat = self.lobby
+            .borrow_mut()
+            .table_mut(table_index)
+            .map(|table| table.join(self.table_output(), player_id, &name));
+
+        match joined_seat {
+            Some(Ok(seat_index)) => self.seat = Some((table_index, seat_index)),
+            Some(Err(error)) => self.send_tables(Some(&format!("Cannot join the table: {}.", error))),
+            None => self.send_tables(Some("This table does not exist.")),
         }
     }
 
-    /// Draws one card from the deck and returns its index and its points amount.
-    fn draw_one_card(&mut self) -> (u8, u8) {
+    /// Lets the player watch the given table as a spectator, the tables are sent again if the table does not exist.
+    ///
+    /// # Args:
+    ///
+    /// `table_index` - the index of the table to watch
+    fn watch_table(
+        &mut self,
+        table_index: usize,
+    ) {
+
+        let watched_table = self.lobby
+            .borrow_mut()
+            .table_mut(table_index)
+            .map(|table| table.watch(self.table_output()));
+
+        match watched_table {
+            Some(Ok(())) => self.watched_table = Some(table_index),
+            Some(Err(error)) => self.send_tables(Some(&format!("Cannot watch the table: {}.", error))),
+            None => self.send_tables(Some("This table does not exist.")),
+        }
+    }
 
-        let card = self.cards.pop().unwrap();
+    /// Seats back the player at his table if his session still exists,
+    /// the tables are sent otherwise so he can choose another table.
+    ///
+    /// # Args:
+    ///
+    /// `session_token` - the session token received by the player when he sat down
+    fn resume_session(
+        &mut self,
+        session_token: &str,
+    ) {
 
-        const ONE_SET_CARDS_AMOUNT: u16 = 52;
-        let index = (card % ONE_SET_CARDS_AMOUNT) as u8;
+        let resumed_seat = self.lobby
+            .borrow_mut()
+            .resume(session_token, self.table_output());
 
-        let points = get_card_points(
-            index,
-            0
-        );
+        match resumed_seat {
+            Some(seat) => self.seat = Some(seat),
+            None => self.send_tables(Some("The session has expired.")),
+        }
+    }
 
-        (index, points)
+    /// Opens a new table and seats the player at it, the shoe of the tables opened by the players
+    /// is always shuffled at random.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the new table
+    /// `rules` - the rules of the new table
+    fn create_table(
+        &mut self,
+        name: String,
+        rules: TableRules,
+    ) {
+
+        let created_table = self.lobby
+            .borrow_mut()
+            .create_table(
+                name,
+                rules,
+                None,
+                vec![],
+                Box::new(RandomShuffle),
+            );
+
+        match created_table {
+            Ok(table_index) => self.join_table(table_index),
+            Err(error) => self.send_tables(Some(&format!("Cannot create the table: {}.", error))),
+        }
     }
 
-    /// Sends one random card to the client through the socket.
-    fn draw_one_player_card(&mut self) {
-
-        let (
-            card_index,
-            card_points
-        ) = self.draw_one_card();
-
-        /* FIXME: we handle only handpoints of the first player for now,
-           of course we should be able to handle all the playing players */
-        let player_handpoints = self.players_handpoints.get_mut(0).unwrap();
-        *player_handpoints += card_points;
-
-        let card_message = SocketMessage {
-            action: MessageAction::SendPlayerCard,
-            card_index: card_index,
-            cards_amount: self.cards.len() as u16,
-            text: "".to_string(),
-            player_handpoints: *player_handpoints,
-            bank_cards: vec![],
+    /// Sends the snapshot of the table the player sits at or watches, so he can rebuild the whole state of the table.
+    /// Returns the reason of the rejection of the request if the player is into the lobby.
+    fn send_table_snapshot(&self) -> std::result::Result<(), Rejection> {
+
+        let table_index = match (self.seat, self.watched_table) {
+            (Some((table_index, _)), _) |
+            (None, Some(table_index)) => table_index,
+            (None, None) => return Err((ErrorCode::IllegalAction, "no table has been joined")),
         };
 
-        let message = serde_json::to_string(&card_message).unwrap();
+        let snapshot_message = self.lobby
+            .borrow_mut()
+            .table_mut(table_index)
+            .unwrap()
+            .snapshot_message();
+
+        self.send(&snapshot_message);
 
-        self.output.send(message).unwrap();
+        Ok(())
     }
 
-    /// Draws the first card to the bank, and render the cards on the client side.
-    fn draw_one_bank_card(&mut self) {
+    /// Forgets the seat of the player if it is not played from this connection anymore,
+    /// as it has been freed or resumed from another connection. The seat cannot be played
+    /// nor left from this connection then.
+    fn forget_lost_seat(&mut self) {
 
-        let (
-            card_index,
-            card_points
-        ) = self.draw_one_card();
+        let (table_index, seat_index) = match self.seat {
+            Some(seat) => seat,
+            None => return,
+        };
 
-        self.bank_cards.push(card_index);
-        self.bank_handpoints = card_points;
+        let is_lost = !self.lobby
+            .borrow_mut()
+            .table_mut(table_index)
+            .unwrap()
+            .is_played_from(seat_index, self.output.connection_id());
 
-        let card_message = SocketMessage {
-            action: MessageAction::SendBankCard,
-            card_index: card_index,
-            cards_amount: self.cards.len() as u16,
-            text: "".to_string(),
-            player_handpoints: self.bank_handpoints,
-            bank_cards: vec![],
-        };
+        if is_lost {
+            self.seat = None;
+        }
+    }
+
+    /// Frees the seat of the player if he sits at a table, or stops sending him the events
+    /// of the table he watches, he goes back to the lobby.
+    fn leave_table(&mut self) {
 
-        let message = serde_json::to_string(&card_message).unwrap();
+        if let Some(table_index) = self.watched_table.take() {
+            self.lobby
+                .borrow_mut()
+                .table_mut(table_index)
+                .unwrap()
+                .stop_watching(self.output.connection_id());
+        }
 
-        self.output.send(message).unwrap();
+        if let Some((table_index, seat_index)) = self.seat.take() {
+            self.lobby
+                .borrow_mut()
+                .table_mut(table_index)
+                .unwrap()
+                .leave(seat_index);
+        }
     }
 
-    /// Draws all the remaining cards of the bank and renders the cards on the client side.
-    fn draw_all_bank_cards(&mut self) {
+    /// Applies the decoded message of the player: nothing is accepted before the player joined the server,
+    /// the lobby messages are handled here and any other message is applied to the seat of the player.
+    /// Returns the reason of the rejection of the message, if it is rejected.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the decoded message
+    fn handle_message(
+        &mut self,
+        message: ClientMessage,
+    ) -> std::result::Result<(), Rejection> {
 
-        const MAX_HAND_POINTS: u8 = 17;
-        while self.bank_handpoints < MAX_HAND_POINTS {
+        if let ClientMessage::Unknown = message {
+            return Err((ErrorCode::UnknownMessage, "the type of the message is unknown"));
+        }
 
-            let (
-                card_index,
-                card_points
-            ) = self.draw_one_card();
+        if self.player.is_none() {
 
-            self.bank_cards.push(card_index);
-            self.bank_handpoints += card_points;
+            if let ClientMessage::Join { name, client_version } = message {
+                return self.greet(&name, client_version);
+            }
+
+            return Err((ErrorCode::IllegalAction, "the player must join the server first"));
         }
 
-        let cards_message = SocketMessage {
-            action: MessageAction::SendBankCards,
-            card_index: 0,
-            cards_amount: 0,
-            text: "".to_string(),
-            player_handpoints: self.bank_handpoints,
-            bank_cards: self.bank_cards.clone(),
-        };
-        let message = serde_json::to_string(&cards_message).unwrap();
-        self.output.send(message).unwrap();
+        self.forget_lost_seat();
+
+        /* the player has to leave his table before joining, creating or watching another one,
+           the spectators cannot send any game action */
+        let in_lobby = self.seat.is_none() &&
+            self.watched_table.is_none();
+
+        match message {
+            ClientMessage::ListTables => self.send_tables(None),
+            ClientMessage::LeaveTable => {
+                self.leave_table();
+                self.send_tables(None);
+            },
+            ClientMessage::Join { .. } => {
+                return Err((ErrorCode::IllegalAction, "the player has already joined the server"));
+            },
+            ClientMessage::JoinTable { .. } |
+            ClientMessage::WatchTable { .. } |
+            ClientMessage::Resume { .. } |
+            ClientMessage::CreateTable { .. } if !in_lobby => {
+                return Err((ErrorCode::IllegalAction, "the current table must be left first"));
+            },
+            ClientMessage::JoinTable { table_index } => self.join_table(table_index as usize),
+            ClientMessage::WatchTable { table_index } => self.watch_table(table_index as usize),
+            ClientMessage::Resume { session_token } => self.resume_session(&session_token),
+            ClientMessage::CreateTable { name, rules } => self.create_table(name, rules),
+            ClientMessage::RequestSnapshot => return self.send_table_snapshot(),
+            message => {
+
+                let (table_index, seat_index) = match self.seat {
+                    Some(seat) => seat,
+                    None if self.watched_table.is_some() => {
+                        return Err((ErrorCode::IllegalAction, "the spectators cannot play"));
+                    },
+                    None => return Err((ErrorCode::IllegalAction, "no table has been joined")),
+                };
+
+                return self.lobby
+                    .borrow_mut()
+                    .table_mut(table_index)
+                    .unwrap()
+                    .handle_message(seat_index, message);
+            },
+        }
+
+        Ok(())
     }
 }
 
 impl Handler for Server {
 
-    /// Called when a new connexion is established from a client. Sends two cards to the new connected client.
+    /// Called when a client requests the connection, selects the encoding of the messages
+    /// from the first known WebSocket subprotocol offered by the client. The messages are encoded in JSON
+    /// if the client offers no known subprotocol.
+    ///
+    /// # Args:
+    ///
+    /// `request` - the handshake request of the client
+    fn on_request(
+        &mut self,
+        request: &Request,
+    ) -> Result<Response> {
+
+        let mut response = Response::from_request(request)?;
+
+        let encoding = Encoding::negotiate(&request.protocols()?);
+
+        if let Some(encoding) = encoding {
+            self.encoding = encoding;
+            response.set_protocol(encoding.subprotocol());
+        }
+
+        Ok(response)
+    }
+
+    /// Called when a new connexion is established from a client. The tables of the lobby are only sent
+    /// once the player joined the server. Registers the connection into the lobby, which checks the timers with it.
     ///
     /// # Args:
     ///
@@ -207,16 +465,44 @@ impl Handler for Server {
     ) -> Result<()> {
 
         println!(
-            "New connexion from {}.",
-            handshake.remote_addr().unwrap().unwrap()
+            "New connexion from {}, encoded in {:?}.",
+            handshake.remote_addr()
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "an unknown address".to_string()),
+            self.encoding,
         );
 
-        self.players_handpoints.push(0);
+        self.lobby
+            .borrow_mut()
+            .open_connection(self.output.clone());
+
+        Ok(())
+    }
+
+    /// Called when a timeout scheduled by the lobby on this connection is triggered, the lobby frees the seats
+    /// of the players disconnected for too long, takes the default actions of the expired decisions
+    /// and schedules the next check.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the token of the triggered timeout
+    fn on_timeout(
+        &mut self,
+        event: Token,
+    ) -> Result<()> {
+
+        self.lobby
+            .borrow_mut()
+            .trigger_timeout(event);
 
         Ok(())
     }
 
-    /// Called when a message is received from the client.
+    /// Called when a message is received from the client. The lobby messages are handled here,
+    /// any other message is applied to the seat of the player at his table.
+    /// The messages which do not use the negotiated encoding, cannot be decoded or are not allowed
+    /// are answered with an error.
     ///
     /// # Args:
     ///
@@ -226,39 +512,45 @@ impl Handler for Server {
         message: Message,
     ) -> Result<()> {
 
-        let data: SocketMessage = serde_json::from_str(
-            &message.into_text()
-                .unwrap()
-        ).unwrap();
-
-        if data.action == MessageAction::Hit {
-            self.draw_one_player_card();
-        }
-        else if data.action == MessageAction::DoubleDown {
-            self.draw_one_player_card();
-            self.draw_all_bank_cards();
-        }
-        else if data.action == MessageAction::Stand ||
-            data.action == MessageAction::Continue {
-            self.draw_all_bank_cards();
+        if message.is_binary() != self.encoding.is_binary() {
+            self.send_error(ErrorCode::MalformedMessage, "the message does not use the negotiated encoding");
+            return Ok(());
         }
-        else if data.action == MessageAction::Restart {
 
-            let player_handpoints = self.players_handpoints.get_mut(0).unwrap();
-            *player_handpoints = 0;
-            self.bank_handpoints = 0;
+        let message: ClientMessage = match self.encoding.decode(&message.into_data()) {
+            Ok(message) => message,
+            Err(error) => {
+                self.send_error(ErrorCode::MalformedMessage, &format!("the message cannot be decoded ({})", error));
+                return Ok(());
+            },
+        };
 
-            self.bank_cards.clear();
-            self.draw_one_player_card();
-            self.draw_one_player_card();
-            self.draw_one_bank_card();
+        if let Err((code, reason)) = self.handle_message(message) {
+            self.send_error(code, reason);
         }
 
         Ok(())
     }
 
-    /// Called when a connexion is terminated from the client side.
+    /// Called when a connexion is terminated from the client side, the seat of the player is kept
+    /// until the end of the grace period so he can resume his session. Stops the spectating
+    /// and unregisters the connection from the lobby.
     fn on_close(&mut self, _: CloseCode, _: &str) {
-        println!("Terminate socket.");
+
+        println!("{} leaves the server.", self.player_label());
+
+        self.lobby
+            .borrow_mut()
+            .close_connection(self.output.connection_id());
+
+        if let Some((table_index, seat_index)) = self.seat.take() {
+            self.lobby
+                .borrow_mut()
+                .table_mut(table_index)
+                .unwrap()
+                .disconnect(seat_index, self.output.connection_id());
+        }
+
+        self.leave_table();
     }
 }
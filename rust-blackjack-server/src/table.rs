@@ -0,0 +1,2118 @@
+//! One table of the lobby, with one shoe, one dealer and several seats.
+
+use rand::{
+    thread_rng,
+    Rng,
+    SeedableRng,
+};
+use rand::rngs::StdRng;
+
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+use std::collections::VecDeque;
+use std::mem;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use blackjack_core::{
+    Card,
+    Hand,
+    HandResult,
+    Rank,
+    Shoe,
+    ShuffleStrategy,
+    SurrenderRule,
+    TableRules,
+    TableSummary,
+    seed_commitment,
+    shoe_commitment,
+    shuffle_seed,
+};
+use blackjack_protocol::{
+    Action,
+    ClientMessage,
+    ErrorCode,
+    Offer,
+    ServerMessage,
+    TableSnapshot,
+};
+
+use crate::output::Output;
+use crate::seat::Seat;
+use crate::round_phase::RoundPhase;
+
+/// The code and the reason of the rejection of a message of a player.
+pub type Rejection = (ErrorCode, &'static str);
+
+/// Returns the seed of the given shoe of a table, made of the first bytes of the SHA-256 hash
+/// of the table seed followed by the shoe number. The seed of a revealed shoe tells nothing
+/// about the seeds of the next shoes.
+///
+/// # Args:
+///
+/// `table_seed` - the seed of the table
+/// `shoe_number` - the number of the shoe, starting at 1
+fn derive_shoe_seed(
+    table_seed: u64,
+    shoe_number: u64,
+) -> u64 {
+
+    let mut hasher = Sha256::new();
+    hasher.update(table_seed.to_be_bytes());
+    hasher.update(shoe_number.to_be_bytes());
+
+    let mut seed_bytes = [0; 8];
+    seed_bytes.copy_from_slice(&hasher.finalize()[..8]);
+
+    u64::from_be_bytes(seed_bytes)
+}
+
+/// Returns a new secret seed (hexadecimal), never drawn from the seeded generators:
+/// it must not be predictable from the logged seeds.
+fn secret_seed() -> String {
+
+    let mut secret_rng = thread_rng();
+
+    format!(
+        "{:016x}{:016x}",
+        secret_rng.gen::<u64>(),
+        secret_rng.gen::<u64>(),
+    )
+}
+
+/// Contains the table name and rules, the cards shoe, the bank hand, the seats of the players and the round phase.
+/// A round is dealt once every seated player placed a bet, the players then play their hands seat by seat.
+/// Every dealt card is sent to all the seated players and spectators, the offers and the bankrolls
+/// are only sent to the concerned player.
+pub struct Table {
+    name: String,
+    rules: TableRules,
+    shoe: Shoe,
+    shuffle_strategy: Box<dyn ShuffleStrategy>,
+    seats: Vec<Option<Seat>>,
+    spectators: Vec<Output>,
+    bank_hand: Hand,
+    phase: RoundPhase,
+    table_seed: u64,
+    shoe_number: u64,
+    shoe_seed: u64,
+    seed_salt: String,
+    next_seed_salt: String,
+    client_seeds: Vec<String>,
+    replayed_client_seeds: VecDeque<Vec<String>>,
+    server_seed: String,
+    shoe_order: Vec<u16>,
+    decision: Option<(RoundPhase, usize, usize)>,
+    decision_deadline: Option<Instant>,
+}
+
+impl Table {
+
+    /// Creates a new table without any player, shuffles all the cards and burns the first one.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the table
+    /// `rules` - the rules of the table
+    /// `table_seed` - the seed every shoe seed is derived from, a random one is used if none is given
+    /// `replayed_client_seeds` - the logged client seeds of the first shoes, used instead of the seeds of the players
+    /// `shuffle_strategy` - the way the shoe is shuffled
+    pub fn new(
+        name: String,
+        rules: TableRules,
+        table_seed: Option<u64>,
+        replayed_client_seeds: Vec<Vec<String>>,
+        shuffle_strategy: Box<dyn ShuffleStrategy>,
+    ) -> Table {
+
+        let shoe = Shoe::new(
+            rules.decks_amount,
+            rules.penetration,
+        );
+
+        let seats = (0..rules.seats_amount)
+            .map(|_| None)
+            .collect();
+
+        let mut table = Table {
+            name,
+            rules,
+            shoe,
+            shuffle_strategy,
+            seats,
+            spectators: vec![],
+            bank_hand: Hand::new(),
+            phase: RoundPhase::Betting,
+            table_seed: table_seed.unwrap_or_else(|| thread_rng().gen()),
+            shoe_number: 0,
+            shoe_seed: 0,
+            seed_salt: String::new(),
+            next_seed_salt: secret_seed(),
+            client_seeds: vec![],
+            replayed_client_seeds: replayed_client_seeds.into(),
+            server_seed: String::new(),
+            shoe_order: vec![],
+            decision: None,
+            decision_deadline: None,
+        };
+
+        table.shuffle_cards();
+
+        table
+    }
+
+    /// Returns the name, the rules and the amount of players of the table, displayed into the lobby.
+    pub fn summary(&self) -> TableSummary {
+        TableSummary {
+            name: self.name.clone(),
+            rules: self.rules.clone(),
+            players_amount: self.seats.iter().flatten().count() as u8,
+        }
+    }
+
+    /// Seats a new player at the first free seat and welcomes him with his seat, the table rules
+    /// and his bankroll, then sends him his session token and the commitment of the current shoe.
+    /// A player sitting down during a round waits for the next one.
+    /// Returns the index of the seat, or the reason why the player cannot sit down:
+    /// the table is full, or another player of the table already uses the same name.
+    ///
+    /// # Args:
+    ///
+    /// `output` - the player output in order to send him information
+    /// `player_id` - the identifier of the player on the server
+    /// `name` - the name of the player
+    pub fn join(
+        &mut self,
+        output: Output,
+        player_id: u32,
+        name: &str,
+    ) -> Result<usize, &'static str> {
+
+        let name_used = self.seats
+            .iter()
+            .flatten()
+            .any(|seat| seat.name.to_lowercase() == name.to_lowercase());
+
+        if name_used {
+            return Err("another player of this table already uses this name");
+        }
+
+        let seat_index = self.seats
+            .iter()
+            .position(Option::is_none)
+            .ok_or("this table is full")?;
+
+        /* the token is only known by the player, it is long enough not to be guessed,
+           and never drawn from the seeded generator of the shoe so it cannot be predicted */
+        let mut rng = thread_rng();
+        let session_token = format!(
+            "{:016x}{:016x}",
+            rng.gen::<u64>(),
+            rng.gen::<u64>(),
+        );
+
+        self.seats[seat_index] = Some(
+            Seat::new(
+                output,
+                player_id,
+                name.to_string(),
+                session_token,
+                self.rules.starting_bankroll,
+            )
+        );
+
+        println!(
+            "Table \"{}\": {} sits down at the seat {}.",
+            self.name,
+            name,
+            seat_index,
+        );
+
+        self.send_welcome(seat_index);
+        self.send_session(seat_index);
+        self.send_to_seat(seat_index, &self.commitment_message());
+
+        self.send_to_seat(seat_index, &self.snapshot_message());
+
+        self.update_decision_timer();
+
+        Ok(seat_index)
+    }
+
+    /// Keeps the seat of a disconnected player for the grace period, the seat is freed
+    /// if he does not resume his session in time. The round waits for him if he has to decide,
+    /// the next rounds are dealt without him if he did not bet. Nothing happens if the player
+    /// already resumed his session from another connection.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the disconnected player
+    /// `connection_id` - the id of the closed connection
+    pub fn disconnect(
+        &mut self,
+        seat_index: usize,
+        connection_id: u32,
+    ) {
+
+        let seat = match self.seats[seat_index].as_mut() {
+            Some(seat) => seat,
+            None => return,
+        };
+
+        if seat.output.connection_id() != connection_id {
+            return;
+        }
+
+        seat.disconnection_time = Some(Instant::now());
+
+        println!(
+            "Table \"{}\": {} got disconnected from the seat {}.",
+            self.name,
+            seat.name,
+            seat_index,
+        );
+
+        /* the disconnected player does not prevent the others from playing the next round */
+        if self.phase == RoundPhase::Betting {
+            self.deal_if_all_bets_placed();
+        }
+
+        self.update_decision_timer();
+    }
+
+    /// Seats back the player holding the given session token and sends him
+    /// the whole current state of the table, so he can finish the round in progress.
+    /// His session token is sent again, so he can resume after the next drop too.
+    /// Returns the index of the seat, or nothing if the session does not exist (anymore).
+    /// A seat still looking connected is taken over and its previous connection is closed,
+    /// as a dropped connection is not always noticed by the server.
+    ///
+    /// # Args:
+    ///
+    /// `session_token` - the session token received by the player when he sat down
+    /// `output` - the player new output in order to send him information
+    pub fn resume(
+        &mut self,
+        session_token: &str,
+        output: Output,
+    ) -> Option<usize> {
+
+        let seat_index = self.seats
+            .iter()
+            .position(|seat| {
+                seat.as_ref()
+                    .is_some_and(|seat| seat.session_token == session_token)
+            })?;
+
+        let seat = self.seat_mut(seat_index);
+
+        if seat.disconnection_time.is_none() {
+            seat.output.close();
+        }
+
+        seat.output = output;
+        seat.disconnection_time = None;
+
+        println!(
+            "Table \"{}\": {} resumes the session of the seat {}.",
+            self.name,
+            self.seat(seat_index).name,
+            seat_index,
+        );
+
+        self.send_snapshot(seat_index);
+        self.send_session(seat_index);
+
+        /* the commitment comes after the snapshot,
+           the player only verifies the cards dealt from now on */
+        self.send_to_seat(seat_index, &self.commitment_message());
+
+        self.update_decision_timer();
+
+        Some(seat_index)
+    }
+
+    /// Frees the seats of the players disconnected for longer than the grace period.
+    pub fn expire_sessions(&mut self) {
+
+        const SESSION_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+        let expired_seats: Vec<usize> = self.seats
+            .iter()
+            .enumerate()
+            .filter(|(_, seat)| {
+                seat.as_ref()
+                    .and_then(|seat| seat.disconnection_time)
+                    .is_some_and(|time| time.elapsed() > SESSION_GRACE_PERIOD)
+            })
+            .map(|(seat_index, _)| seat_index)
+            .collect();
+
+        for seat_index in expired_seats {
+            self.leave(seat_index);
+        }
+    }
+
+    /// Lets a spectator watch the table, he receives the table rules, the commitment of the current shoe,
+    /// the snapshot of the table and then all the public events of the table.
+    /// Returns the reason why the table cannot be watched: the amount of spectators is limited,
+    /// as every event of the table is queued once for each one of them.
+    ///
+    /// # Args:
+    ///
+    /// `output` - the spectator output in order to send him information
+    pub fn watch(
+        &mut self,
+        output: Output,
+    ) -> Result<(), &'static str> {
+
+        const MAX_SPECTATORS_AMOUNT: usize = 16;
+
+        if self.spectators.len() >= MAX_SPECTATORS_AMOUNT {
+            return Err("the maximum amount of spectators is reached");
+        }
+
+        let spectating_message = ServerMessage::Spectating {
+            rules: self.rules.clone(),
+            cards_amount: self.shoe.len() as u16,
+        };
+
+        output.send(&spectating_message);
+        output.send(&self.commitment_message());
+        output.send(&self.snapshot_message());
+
+        self.spectators.push(output);
+
+        Ok(())
+    }
+
+    /// Stops sending the table events to a leaving spectator.
+    ///
+    /// # Args:
+    ///
+    /// `connection_id` - the connection id of the output of the spectator
+    pub fn stop_watching(
+        &mut self,
+        connection_id: u32,
+    ) {
+        self.spectators
+            .retain(|spectator| spectator.connection_id() != connection_id);
+    }
+
+    /// Frees the seat of a leaving player. His hands are discarded and his bets are lost,
+    /// the round goes on without him if nobody else has to decide.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the leaving player
+    pub fn leave(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = match self.seats[seat_index].take() {
+            Some(seat) => seat,
+            None => return,
+        };
+
+        println!(
+            "Table \"{}\": {} leaves the seat {}.",
+            self.name,
+            seat.name,
+            seat_index,
+        );
+
+        /* the cards of the previous rounds are already discarded */
+        if seat.in_round {
+            self.shoe.discard(&seat.cards());
+        }
+
+        /* the round is over without any player, the bank cards are discarded without being revealed */
+        if self.phase != RoundPhase::Betting &&
+            !self.seats.iter().flatten().any(|seat| seat.in_round) {
+            self.discard_round_cards();
+            self.bank_hand.clear();
+            self.phase = RoundPhase::Betting;
+        }
+
+        /* the round goes on without the leaving player */
+        match self.phase {
+            RoundPhase::Betting => self.deal_if_all_bets_placed(),
+            RoundPhase::EarlySurrender if !self.is_anyone_deciding() => self.offer_insurance_or_play(),
+            RoundPhase::Insurance if !self.is_anyone_deciding() => self.start_player_turn(),
+            RoundPhase::PlayerTurn(active_seat_index) if active_seat_index == seat_index => {
+                self.next_player_turn(seat_index + 1);
+            },
+            _ => {},
+        }
+
+        self.update_decision_timer();
+    }
+
+    /// Indicates if the given seat is played from the given connection. A seat freed,
+    /// or resumed from another connection, is not played from the connection anymore.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    /// `connection_id` - the id of the connection
+    pub fn is_played_from(
+        &self,
+        seat_index: usize,
+        connection_id: u32,
+    ) -> bool {
+        self.seats[seat_index]
+            .as_ref()
+            .is_some_and(|seat| seat.output.connection_id() == connection_id)
+    }
+
+    /// Applies the action sent by the player of the given seat, if it is one of his legal actions.
+    /// Any other action is rejected with the reason of the rejection.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the player
+    /// `message` - the message received from the player
+    pub fn handle_message(
+        &mut self,
+        seat_index: usize,
+        message: ClientMessage,
+    ) -> Result<(), Rejection> {
+
+        if self.seats[seat_index].is_none() {
+            return Err((ErrorCode::IllegalAction, "the seat has been freed"));
+        }
+
+        /* the seeds are accepted at any time, they are only used at the next shuffle */
+        if let ClientMessage::ClientSeed { seed } = message {
+
+            const MAX_CLIENT_SEED_LENGTH: usize = 64;
+
+            if seed.is_empty() ||
+                seed.len() > MAX_CLIENT_SEED_LENGTH {
+                return Err((ErrorCode::MalformedMessage, "the client seed must contain between 1 and 64 characters"));
+            }
+
+            self.seat_mut(seat_index).client_seed = Some(seed);
+
+            return Ok(());
+        }
+
+        let is_legal = message.action()
+            .is_some_and(|action| self.legal_actions(seat_index).contains(&action));
+
+        if !is_legal {
+            return Err(self.rejection(seat_index, &message));
+        }
+
+        match message {
+            ClientMessage::PlaceBet { amount } => self.place_bet(seat_index, amount)?,
+            ClientMessage::DeclineSurrender => self.close_early_surrender(seat_index),
+            ClientMessage::Insurance { stake } => self.close_insurance(seat_index, stake),
+            ClientMessage::DeclineInsurance => self.close_insurance(seat_index, 0),
+            ClientMessage::Surrender if self.phase == RoundPhase::EarlySurrender => self.surrender(seat_index),
+            message => self.play_active_hand(seat_index, message),
+        }
+
+        self.update_decision_timer();
+
+        Ok(())
+    }
+
+    /// Returns the actions the player of the given seat can take now, none if the table does not wait for him.
+    /// During his turn, only the actions allowed by the table rules on his active hand,
+    /// and covered by his bankroll, are listed.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn legal_actions(
+        &self,
+        seat_index: usize,
+    ) -> Vec<Action> {
+
+        let seat = match &self.seats[seat_index] {
+            Some(seat) => seat,
+            None => return vec![],
+        };
+
+        match self.phase {
+            RoundPhase::Betting if !seat.in_round && seat.bet == 0 => vec![Action::PlaceBet],
+            RoundPhase::EarlySurrender if seat.surrender_open => vec![
+                Action::Surrender,
+                Action::DeclineSurrender,
+            ],
+            RoundPhase::Insurance if seat.insurance_open => vec![
+                Action::Insurance,
+                Action::DeclineInsurance,
+            ],
+            RoundPhase::PlayerTurn(active_seat_index) if active_seat_index == seat_index => {
+
+                const HAND_ACTIONS: [Action; 5] = [
+                    Action::Hit,
+                    Action::Stand,
+                    Action::DoubleDown,
+                    Action::Split,
+                    Action::Surrender,
+                ];
+
+                HAND_ACTIONS.iter()
+                    .copied()
+                    .filter(|action| self.check_active_hand_action(seat_index, *action).is_ok())
+                    .collect()
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Checks if the active player can take the given action on his active hand,
+    /// according to the table rules and to his bankroll.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the active player
+    /// `action` - the action to check
+    fn check_active_hand_action(
+        &self,
+        seat_index: usize,
+        action: Action,
+    ) -> Result<(), Rejection> {
+
+        let seat = self.seat(seat_index);
+        let hands_amount = seat.hands.len();
+
+        let active_hand = match seat.hands.get(seat.active_hand) {
+            Some(active_hand) => active_hand,
+            None => return Err((ErrorCode::IllegalAction, "all the hands of this seat are finished")),
+        };
+
+        /* doubling down and splitting both require the stake of the active hand once more */
+        let can_afford_active_hand_bet = seat.bankroll >= seat.hand_bets[seat.active_hand];
+
+        match action {
+            Action::Hit if !self.rules.can_hit(active_hand) => {
+                Err((ErrorCode::IllegalAction, "the active hand cannot be hit"))
+            },
+            Action::DoubleDown if !self.rules.can_double(active_hand) => {
+                Err((ErrorCode::IllegalAction, "the active hand cannot be doubled down"))
+            },
+            Action::Split if !self.rules.can_split(active_hand, hands_amount) => {
+                Err((ErrorCode::IllegalAction, "the active hand cannot be split"))
+            },
+            Action::Hit |
+            Action::DoubleDown if self.shoe.drawable_len() < 1 => {
+                Err((ErrorCode::IllegalAction, "no card is left to draw"))
+            },
+            /* the split hands both receive a card */
+            Action::Split if self.shoe.drawable_len() < 2 => {
+                Err((ErrorCode::IllegalAction, "no card is left to draw"))
+            },
+            Action::DoubleDown |
+            Action::Split if !can_afford_active_hand_bet => {
+                Err((ErrorCode::InsufficientFunds, "the bankroll is not enough to cover the bet of the active hand once more"))
+            },
+            /* late surrender, once the bank peeked for a blackjack */
+            Action::Surrender if !self.rules.can_surrender(active_hand) => {
+                Err((ErrorCode::IllegalAction, "the active hand cannot be surrendered"))
+            },
+            Action::Hit |
+            Action::Stand |
+            Action::DoubleDown |
+            Action::Split |
+            Action::Surrender => Ok(()),
+            _ => Err((ErrorCode::IllegalAction, "this action is not allowed during the player turn")),
+        }
+    }
+
+    /// Returns the reason why the given message of the player of the given seat is not one of his legal actions.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the player
+    /// `message` - the rejected message
+    fn rejection(
+        &self,
+        seat_index: usize,
+        message: &ClientMessage,
+    ) -> Rejection {
+
+        const OUT_OF_PHASE: Rejection = (
+            ErrorCode::IllegalAction,
+            "this action is not allowed during the current phase of the round",
+        );
+
+        let action = match message.action() {
+            Some(action) => action,
+            None => return OUT_OF_PHASE,
+        };
+
+        match (self.phase, action) {
+            (RoundPhase::Betting, Action::PlaceBet) => {
+                (ErrorCode::IllegalAction, "the bet of this round is already placed")
+            },
+            (RoundPhase::EarlySurrender, Action::Surrender) |
+            (RoundPhase::EarlySurrender, Action::DeclineSurrender) => {
+                (ErrorCode::IllegalAction, "the surrender is not offered to this seat")
+            },
+            (RoundPhase::Insurance, Action::Insurance) |
+            (RoundPhase::Insurance, Action::DeclineInsurance) => {
+                (ErrorCode::IllegalAction, "the insurance is not offered to this seat")
+            },
+            (RoundPhase::PlayerTurn(active_seat_index), action) if active_seat_index == seat_index => {
+                self.check_active_hand_action(seat_index, action)
+                    .err()
+                    .unwrap_or(OUT_OF_PHASE)
+            },
+            (RoundPhase::PlayerTurn(_), _) => (ErrorCode::IllegalAction, "this is not the turn of this seat"),
+            _ => OUT_OF_PHASE,
+        }
+    }
+
+    /// Takes the default action for the players who did not decide before the end of the decision time:
+    /// the players who did not bet sit out, the surrender and the insurance are declined
+    /// and the active hand stands.
+    pub fn expire_decision(&mut self) {
+
+        let expired = self.decision_deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+
+        if !expired {
+            return;
+        }
+
+        let awaited_seats: Vec<usize> = (0..self.seats.len())
+            .filter(|seat_index| self.is_awaited(*seat_index))
+            .collect();
+
+        match self.phase {
+            RoundPhase::Betting => {
+
+                for seat_index in awaited_seats {
+                    self.seat_mut(seat_index).sitting_out = true;
+                }
+
+                self.deal_if_all_bets_placed();
+            },
+            RoundPhase::EarlySurrender => {
+
+                for seat_index in awaited_seats {
+                    self.close_early_surrender(seat_index);
+                }
+            },
+            RoundPhase::Insurance => {
+
+                for seat_index in awaited_seats {
+                    self.close_insurance(seat_index, 0);
+                }
+            },
+            RoundPhase::PlayerTurn(active_seat_index) => self.finish_active_hand(active_seat_index),
+            _ => {},
+        }
+
+        self.update_decision_timer();
+    }
+
+    /// Applies the action of the active player on his active hand, the action has already been checked.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the active player
+    /// `message` - the action of the player
+    fn play_active_hand(
+        &mut self,
+        seat_index: usize,
+        message: ClientMessage,
+    ) {
+
+        let seat = self.seat(seat_index);
+        let active_hand_index = seat.active_hand;
+        let active_hand_bet = seat.hand_bets[active_hand_index];
+
+        match message {
+            ClientMessage::Hit => {
+                self.draw_one_player_card(seat_index, active_hand_index);
+                self.update_player_turn(seat_index);
+            },
+            ClientMessage::DoubleDown => {
+                self.take_stake(seat_index, active_hand_bet);
+                self.seat_mut(seat_index).hand_bets[active_hand_index] *= 2;
+                self.draw_one_player_card(seat_index, active_hand_index);
+                self.finish_active_hand(seat_index);
+            },
+            ClientMessage::Split => {
+                self.take_stake(seat_index, active_hand_bet);
+                self.split_active_hand(seat_index);
+                self.update_player_turn(seat_index);
+            },
+            ClientMessage::Surrender => self.surrender(seat_index),
+            ClientMessage::Stand => self.finish_active_hand(seat_index),
+            _ => {},
+        }
+    }
+
+    /// Returns the seat at the given index, which must be occupied.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn seat(
+        &self,
+        seat_index: usize,
+    ) -> &Seat {
+        self.seats[seat_index]
+            .as_ref()
+            .unwrap()
+    }
+
+    /// Returns the mutable seat at the given index, which must be occupied.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn seat_mut(
+        &mut self,
+        seat_index: usize,
+    ) -> &mut Seat {
+        self.seats[seat_index]
+            .as_mut()
+            .unwrap()
+    }
+
+    /// Returns the indices of the seats playing the current round, in the dealing order.
+    fn round_seats(&self) -> Vec<usize> {
+        self.seats
+            .iter()
+            .enumerate()
+            .filter(|(_, seat)| seat.as_ref().is_some_and(|seat| seat.in_round))
+            .map(|(seat_index, _)| seat_index)
+            .collect()
+    }
+
+    /// Indicates if a player still has to take an insurance or early surrender decision.
+    fn is_anyone_deciding(&self) -> bool {
+        self.seats
+            .iter()
+            .flatten()
+            .any(Seat::is_deciding)
+    }
+
+    /// Returns the decision the table is waiting for, if any: the phase, the active hand and the amount
+    /// of cards of the active seat, so a new card restarts the decision time. The bets are only timed
+    /// once a first player has bet, an empty table does not make the players sit out.
+    fn current_decision(&self) -> Option<(RoundPhase, usize, usize)> {
+
+        if self.rules.decision_time == 0 {
+            return None;
+        }
+
+        match self.phase {
+            RoundPhase::Betting if self.seats.iter().flatten().any(|seat| seat.bet > 0) => {
+                Some((self.phase, 0, 0))
+            },
+            RoundPhase::EarlySurrender |
+            RoundPhase::Insurance => Some((self.phase, 0, 0)),
+            RoundPhase::PlayerTurn(active_seat_index) => {
+
+                let seat = self.seat(active_seat_index);
+                let cards_amount = seat.hands
+                    .iter()
+                    .map(|hand| hand.cards().len())
+                    .sum();
+
+                Some((self.phase, seat.active_hand, cards_amount))
+            },
+            _ => None,
+        }
+    }
+
+    /// Indicates if the table is waiting for a decision of the player of the given seat.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn is_awaited(
+        &self,
+        seat_index: usize,
+    ) -> bool {
+
+        let seat = match &self.seats[seat_index] {
+            Some(seat) => seat,
+            None => return false,
+        };
+
+        match self.phase {
+            RoundPhase::Betting => seat.is_connected() && !seat.sitting_out && seat.bet == 0,
+            RoundPhase::EarlySurrender => seat.surrender_open,
+            RoundPhase::Insurance => seat.insurance_open,
+            RoundPhase::PlayerTurn(active_seat_index) => active_seat_index == seat_index,
+            _ => false,
+        }
+    }
+
+    /// Restarts the decision time when the table waits for a new decision
+    /// and sends their countdown and their legal actions to all the players.
+    fn update_decision_timer(&mut self) {
+
+        let decision = self.current_decision();
+
+        if decision != self.decision {
+            self.decision = decision;
+            self.decision_deadline = decision.map(|_| {
+                Instant::now() + Duration::from_secs(self.rules.decision_time as u64)
+            });
+        }
+
+        for seat_index in 0..self.seats.len() {
+            self.send_countdown(seat_index);
+            self.send_prompt(seat_index);
+        }
+    }
+
+    /// Sends the actions the player of the given seat can take now, none if the table does not wait for him.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn send_prompt(
+        &self,
+        seat_index: usize,
+    ) {
+
+        let prompt_message = ServerMessage::Prompt {
+            actions: self.legal_actions(seat_index),
+        };
+
+        self.send_to_seat(seat_index, &prompt_message);
+    }
+
+    /// Sends the remaining seconds of the decision time to the player of the given seat,
+    /// or 0 if the table is not waiting for his decision.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn send_countdown(
+        &self,
+        seat_index: usize,
+    ) {
+
+        if self.seats[seat_index].is_none() {
+            return;
+        }
+
+        let countdown = match self.decision_deadline {
+            Some(deadline) if self.is_awaited(seat_index) => {
+
+                /* rounded up, the player never sees 0 while he can still decide */
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                (remaining.as_millis() as u64).div_ceil(1000) as u16
+            },
+            _ => 0,
+        };
+
+        let countdown_message = ServerMessage::Countdown {
+            seconds: countdown,
+        };
+
+        self.send_to_seat(seat_index, &countdown_message);
+    }
+
+    /// Sends the given message to the player of the given seat only, if he is connected.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the player
+    /// `message` - the message to send
+    fn send_to_seat(
+        &self,
+        seat_index: usize,
+        message: &ServerMessage,
+    ) {
+
+        if let Some(seat) = &self.seats[seat_index] {
+
+            if !seat.is_connected() {
+                return;
+            }
+
+            seat.output.send(message);
+        }
+    }
+
+    /// Sends the given message to all the spectators of the table.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    fn send_to_spectators(
+        &self,
+        message: &ServerMessage,
+    ) {
+
+        for spectator in &self.spectators {
+            spectator.send(message);
+        }
+    }
+
+    /// Sends the given message to all the connected players and all the spectators,
+    /// every one of them with the encoding of his connection.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    fn broadcast(
+        &self,
+        message: &ServerMessage,
+    ) {
+
+        let connected_seats = self.seats
+            .iter()
+            .flatten()
+            .filter(|seat| seat.is_connected());
+
+        for seat in connected_seats {
+            seat.output.send(message);
+        }
+
+        self.send_to_spectators(message);
+    }
+
+    /// Draws one card from the shoe, returns nothing if no card is left at all:
+    /// the players are not offered the actions drawing cards anymore and the bank stands then.
+    fn draw_one_card(&mut self) -> Option<Card> {
+
+        /* the cut card is supposed to be reached long before the shoe is empty,
+           but a full penetration with many split hands can still empty it during a round,
+           the discards of the previous rounds are then shuffled back */
+        if self.shoe.is_empty() &&
+            self.shoe.drawable_len() > 0 {
+            self.shuffle_shoe();
+        }
+
+        self.shoe.draw()
+    }
+
+    /// Reveals the order and the seeds of the finished shoe, shuffles the discards back into the shoe
+    /// with the seed of the new shoe mixed with the seeds sent by the players, commits to the new order
+    /// and burns the first card if the strategy does. Every shoe seed is derived from the table seed
+    /// and the number of the shoe, both are logged with the client seeds so the shoe can be dealt again.
+    /// The shoe seed has been committed with the previous shoe, before the players sent their seeds.
+    /// The seeds of the players are ignored as long as logged client seeds are replayed.
+    fn shuffle_cards(&mut self) {
+
+        if !self.shoe_order.is_empty() {
+            self.reveal_shoe();
+        }
+
+        self.shoe_number += 1;
+        self.shoe_seed = derive_shoe_seed(
+            self.table_seed,
+            self.shoe_number,
+        );
+        self.seed_salt = mem::replace(
+            &mut self.next_seed_salt,
+            secret_seed(),
+        );
+
+        let client_seeds: Vec<String> = self.seats
+            .iter_mut()
+            .flatten()
+            .filter_map(|seat| seat.client_seed.take())
+            .collect();
+
+        self.client_seeds = self.replayed_client_seeds
+            .pop_front()
+            .unwrap_or(client_seeds);
+
+        println!(
+            "Table \"{}\": {} of the shoe {} of the table seed {} (shoe seed {}) with the client seeds {:?}.",
+            self.name,
+            self.shuffle_strategy.name(),
+            self.shoe_number,
+            self.table_seed,
+            self.shoe_seed,
+            self.client_seeds,
+        );
+
+        let mut rng = StdRng::from_seed(shuffle_seed(
+            self.shoe_seed,
+            &self.client_seeds,
+        ));
+        self.shoe.shuffle(&*self.shuffle_strategy, &mut rng);
+
+        self.server_seed = secret_seed();
+        self.shoe_order = self.shoe.order();
+
+        if self.shuffle_strategy.burns_card() {
+            self.shoe.burn();
+        }
+
+        self.broadcast(&self.commitment_message());
+    }
+
+    /// Returns the message holding the complete public state of the table: the occupied seats with their hands,
+    /// the bank cards (only the up card during a round), the phase, the active seat and the remaining cards.
+    /// It is sent to the players sitting down or resuming their session, to the new spectators,
+    /// and to anyone requesting it.
+    pub fn snapshot_message(&self) -> ServerMessage {
+
+        let active_seat = match self.phase {
+            RoundPhase::PlayerTurn(seat_index) => Some(seat_index as u8),
+            _ => None,
+        };
+
+        /* the hole card stays hidden until the bank cards are revealed at the settlement,
+           they remain revealed until the next round is dealt */
+        let hole_card = self.phase != RoundPhase::Betting &&
+            self.phase != RoundPhase::Settlement &&
+            self.bank_hand.cards().len() > 1;
+
+        let (bank_cards, bank_points) = if hole_card {
+
+            let up_card = self.bank_hand.cards()[0];
+            (vec![up_card.wire_id()], up_card.points())
+        } else {
+            (
+                self.bank_hand
+                    .cards()
+                    .iter()
+                    .map(|card| card.wire_id())
+                    .collect(),
+                self.bank_hand.soft_total(),
+            )
+        };
+
+        ServerMessage::TableSnapshot {
+            snapshot: TableSnapshot {
+                phase: self.phase.table_phase(),
+                active_seat,
+                seats: self.seats
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(seat_index, seat)| {
+                        seat.as_ref()
+                            .map(|seat| seat.snapshot(seat_index))
+                    })
+                    .collect(),
+                bank_cards,
+                bank_points,
+                hole_card,
+                cards_amount: self.shoe.len() as u16,
+            },
+        }
+    }
+
+    /// Returns the message holding the commitment of the order of the current shoe, published before
+    /// its first card is dealt. The order is revealed at the next shuffle so the players can verify their cards.
+    /// It also holds the commitment of the seed of the next shoe, the seeds sent by the players from now on
+    /// are mixed into the shuffle of that next shoe.
+    fn commitment_message(&self) -> ServerMessage {
+
+        let next_shoe_seed = derive_shoe_seed(
+            self.table_seed,
+            self.shoe_number + 1,
+        );
+
+        ServerMessage::ShoeCommitment {
+            commitment: shoe_commitment(&self.shoe_order, &self.server_seed),
+            next_seed_commitment: seed_commitment(next_shoe_seed, &self.next_seed_salt),
+        }
+    }
+
+    /// Sends the order, the server seed, the shoe seed with its salt and the client seeds of the finished shoe
+    /// to all the players and spectators, the first card of the order is the burnt one.
+    /// The players recompute the order from the seeds when the strategy of the table allows it.
+    fn reveal_shoe(&mut self) {
+
+        let reveal_message = ServerMessage::ShoeReveal {
+            server_seed: self.server_seed.clone(),
+            cards: self.shoe_order.clone(),
+            shoe_seed: self.shoe_seed,
+            seed_salt: self.seed_salt.clone(),
+            client_seeds: self.client_seeds.clone(),
+            recomputable: self.shuffle_strategy.is_recomputable(),
+        };
+
+        self.broadcast(&reveal_message);
+    }
+
+    /// Shuffles the discards back into the shoe, burns the first card
+    /// and notifies the players the shoe has been renewed.
+    fn shuffle_shoe(&mut self) {
+
+        self.shuffle_cards();
+
+        let shuffle_message = ServerMessage::Shuffle {
+            cards_amount: self.shoe.len() as u16,
+        };
+
+        self.broadcast(&shuffle_message);
+    }
+
+    /// Discards all the cards of the finished round, then shuffles the shoe if the cut card has been reached,
+    /// so the order of the next round is committed before any bet is placed.
+    /// The cards remain displayed until the next round is dealt.
+    fn discard_round_cards(&mut self) {
+
+        let mut round_cards: Vec<Card> = self.seats
+            .iter()
+            .flatten()
+            .filter(|seat| seat.in_round)
+            .flat_map(Seat::cards)
+            .collect();
+
+        round_cards.extend_from_slice(self.bank_hand.cards());
+
+        self.shoe.discard(&round_cards);
+
+        /* a continuous shuffling machine takes the discards back after every round */
+        if self.shoe.cut_card_reached() ||
+            self.shuffle_strategy.is_continuous() {
+            self.shuffle_shoe();
+        }
+    }
+
+    /// Sends one card to all the players, for the given hand of the given seat.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat receiving the card
+    /// `hand_index` - the index of the player hand receiving the card
+    fn draw_one_player_card(
+        &mut self,
+        seat_index: usize,
+        hand_index: usize,
+    ) {
+
+        let card = match self.draw_one_card() {
+            Some(card) => card,
+            None => return,
+        };
+
+        let player_hand = self.seat_mut(seat_index)
+            .hands
+            .get_mut(hand_index)
+            .unwrap();
+        player_hand.add(card);
+        let player_handpoints = player_hand.soft_total();
+
+        let card_message = ServerMessage::PlayerCard {
+            seat_index: seat_index as u8,
+            hand_index: hand_index as u8,
+            card: card.wire_id(),
+            points: player_handpoints,
+            cards_amount: self.shoe.len() as u16,
+        };
+
+        self.broadcast(&card_message);
+    }
+
+    /// Splits the active hand of the given seat into two hands, each one receiving a second card.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat splitting its active hand
+    fn split_active_hand(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = self.seat_mut(seat_index);
+        let active_hand = seat.active_hand;
+
+        let new_hand = seat.hands
+            .get_mut(active_hand)
+            .unwrap()
+            .split();
+
+        seat.hands.insert(
+            active_hand + 1,
+            new_hand,
+        );
+
+        /* the new hand is played for the same bet as the split one */
+        let split_bet = seat.hand_bets[active_hand];
+        seat.hand_bets.insert(
+            active_hand + 1,
+            split_bet,
+        );
+
+        let split_message = ServerMessage::Split {
+            seat_index: seat_index as u8,
+            hand_index: active_hand as u8,
+        };
+
+        self.broadcast(&split_message);
+
+        self.draw_one_player_card(seat_index, active_hand);
+        self.draw_one_player_card(seat_index, active_hand + 1);
+    }
+
+    /// Skips all the hands of the given seat without any possible decision (busted, 21 points or split aces)
+    /// and notifies the players of the new active hand of the seat.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn update_active_hand(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let rules = &self.rules;
+        let seat = self.seats[seat_index]
+            .as_mut()
+            .unwrap();
+        let hands_amount = seat.hands.len();
+
+        while seat.active_hand < hands_amount &&
+            !rules.is_playable(
+                &seat.hands[seat.active_hand],
+                hands_amount,
+            ) {
+            seat.active_hand += 1;
+        }
+
+        self.send_active_hand(seat_index);
+    }
+
+    /// Skips the finished hands of the active player, the turn goes to the next player
+    /// once all his hands are finished.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the active player
+    fn update_player_turn(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        self.update_active_hand(seat_index);
+
+        if !self.seat(seat_index).is_playing() {
+            self.next_player_turn(seat_index + 1);
+        }
+    }
+
+    /// Gives the turn to the first player from the given seat who still has a hand to play,
+    /// or to the bank once all the players played.
+    ///
+    /// # Args:
+    ///
+    /// `first_seat_index` - the index of the first seat which can get the turn
+    fn next_player_turn(
+        &mut self,
+        first_seat_index: usize,
+    ) {
+
+        let next_seats = self.round_seats()
+            .into_iter()
+            .filter(|seat_index| *seat_index >= first_seat_index);
+
+        for seat_index in next_seats {
+
+            self.update_active_hand(seat_index);
+
+            if self.seat(seat_index).is_playing() {
+                self.phase = RoundPhase::PlayerTurn(seat_index);
+                self.send_active_seat(seat_index);
+                return;
+            }
+        }
+
+        self.dealer_turn();
+    }
+
+    /// Notifies all the players of the seat of the active player.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the active player
+    fn send_active_seat(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let active_seat_message = ServerMessage::ActiveSeat {
+            seat_index: seat_index as u8,
+        };
+
+        self.broadcast(&active_seat_message);
+    }
+
+    /// Lets the bank play once all the hands of all the players are finished: reveals its hole card,
+    /// draws its remaining cards one by one, then ends the round.
+    fn dealer_turn(&mut self) {
+
+        self.phase = RoundPhase::DealerTurn;
+
+        /* the bank does not need to draw if no player hand can beat it anymore,
+           all the players having surrendered or been paid for their natural */
+        let bank_must_play = self.seats
+            .iter()
+            .flatten()
+            .any(|seat| {
+                seat.in_round &&
+                    !seat.surrendered &&
+                    !(seat.hands.len() == 1 && seat.has_blackjack())
+            });
+
+        self.send_bank_cards();
+
+        if bank_must_play {
+            self.draw_all_bank_cards();
+        }
+
+        self.finish_round();
+    }
+
+    /// Notifies the players of the active hand of the given seat,
+    /// equal to the hands amount when no hand is left to play.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn send_active_hand(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let active_hand_message = ServerMessage::ActiveHand {
+            seat_index: seat_index as u8,
+            hand_index: self.seat(seat_index).active_hand as u8,
+        };
+
+        self.broadcast(&active_hand_message);
+    }
+
+    /// Takes the bet of the player from his bankroll, the round is dealt once all the seated players have bet.
+    /// The bet is rejected if it does not respect the table limits or if the bankroll is not enough.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the player
+    /// `amount` - the amount of the bet
+    fn place_bet(
+        &mut self,
+        seat_index: usize,
+        amount: u32,
+    ) -> Result<(), Rejection> {
+
+        let seat = self.seat(seat_index);
+
+        if !self.rules.is_valid_bet(amount) {
+            return Err((ErrorCode::InvalidBet, "the bet does not respect the table limits"));
+        }
+
+        if amount > seat.bankroll {
+            return Err((ErrorCode::InsufficientFunds, "the bankroll is not enough for this bet"));
+        }
+
+        let seat = self.seat_mut(seat_index);
+        seat.bet = amount;
+        seat.sitting_out = false;
+
+        self.take_stake(seat_index, amount);
+
+        self.deal_if_all_bets_placed();
+
+        Ok(())
+    }
+
+    /// Deals a new round if the players are betting and all the connected players have bet,
+    /// the disconnected players and the players sitting out who did not bet sit the round out.
+    fn deal_if_all_bets_placed(&mut self) {
+
+        let mut seats = self.seats
+            .iter()
+            .flatten()
+            .filter(|seat| (seat.is_connected() && !seat.sitting_out) || seat.bet > 0)
+            .peekable();
+
+        if self.phase != RoundPhase::Betting ||
+            seats.peek().is_none() ||
+            !seats.all(|seat| seat.bet > 0) {
+            return;
+        }
+
+        self.deal_new_round();
+    }
+
+    /// Takes the given stake from the bankroll of the player and notifies him of his new bankroll.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the player
+    /// `stake` - the amount to take, never more than the bankroll
+    fn take_stake(
+        &mut self,
+        seat_index: usize,
+        stake: u32,
+    ) {
+        self.seat_mut(seat_index).bankroll -= stake;
+        self.send_bankroll(seat_index);
+    }
+
+    /// Sends the current bankroll to the player of the given seat.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the player
+    fn send_bankroll(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let bankroll_message = ServerMessage::Bankroll {
+            amount: self.seat(seat_index).bankroll,
+        };
+
+        self.send_to_seat(seat_index, &bankroll_message);
+    }
+
+    /// Deals the two first cards of every player and of the bank (one face-up, one face-down),
+    /// one card at a time, then offers the early surrender if the bank could have a blackjack
+    /// and the table allows it.
+    fn deal_new_round(&mut self) {
+
+        self.phase = RoundPhase::Dealing;
+
+        self.bank_hand.clear();
+
+        for seat in self.seats.iter_mut().flatten() {
+
+            if seat.bet > 0 {
+                seat.start_round();
+            } else {
+                seat.hands.clear();
+            }
+        }
+
+        let new_round_message = ServerMessage::NewRound;
+
+        self.broadcast(&new_round_message);
+
+        let round_seats = self.round_seats();
+
+        for seat_index in &round_seats {
+            self.draw_one_player_card(*seat_index, 0);
+        }
+
+        self.draw_one_bank_card();
+
+        for seat_index in &round_seats {
+            self.draw_one_player_card(*seat_index, 0);
+        }
+
+        self.draw_hole_card();
+
+        self.offer_early_surrender();
+    }
+
+    /// Opens the early surrender decision window of every player without a natural,
+    /// if the table allows it and the bank could have a blackjack.
+    /// The players can give up half of their bet before the bank peeks for a blackjack.
+    fn offer_early_surrender(&mut self) {
+
+        let up_card = self.bank_hand
+            .cards()
+            .first()
+            .unwrap();
+
+        if self.rules.surrender != SurrenderRule::Early ||
+            !self.rules.dealer_peeks(up_card) {
+            self.offer_insurance_or_play();
+            return;
+        }
+
+        /* a natural is never surrendered, the early surrender offer would be pointless */
+        for seat_index in self.round_seats() {
+
+            let seat = self.seat_mut(seat_index);
+
+            if seat.has_blackjack() {
+                continue;
+            }
+
+            seat.surrender_open = true;
+            let offered_amount = seat.bet / 2;
+
+            let offer_message = ServerMessage::Offer {
+                offer: Offer::Surrender,
+                amount: offered_amount,
+            };
+
+            self.send_to_seat(seat_index, &offer_message);
+        }
+
+        if self.is_anyone_deciding() {
+            self.phase = RoundPhase::EarlySurrender;
+        } else {
+            self.offer_insurance_or_play();
+        }
+    }
+
+    /// Closes the early surrender decision window of the given seat,
+    /// the insurance is offered once all the players decided.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat declining the surrender
+    fn close_early_surrender(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        self.seat_mut(seat_index).surrender_open = false;
+
+        if !self.is_anyone_deciding() {
+            self.offer_insurance_or_play();
+        }
+    }
+
+    /// Offers the insurance if the bank shows an ace, or lets the bank peek for a blackjack.
+    /// All the players decide on the insurance at the same time.
+    fn offer_insurance_or_play(&mut self) {
+
+        let up_card = self.bank_hand
+            .cards()
+            .first()
+            .unwrap();
+
+        if up_card.rank != Rank::Ace {
+            self.start_player_turn();
+            return;
+        }
+
+        for seat_index in self.round_seats() {
+
+            if self.seat(seat_index).surrendered {
+                continue;
+            }
+
+            self.offer_insurance(seat_index);
+        }
+
+        if self.is_anyone_deciding() {
+            self.phase = RoundPhase::Insurance;
+        } else {
+            self.start_player_turn();
+        }
+    }
+
+    /// Opens the insurance decision window of the given seat, the player can bet up to half of his bet
+    /// that the bank has a blackjack. A player holding a natural is offered even money instead,
+    /// the offered amount is then his bet, paid 1:1 if he takes it.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn offer_insurance(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = self.seat_mut(seat_index);
+        seat.insurance_open = true;
+
+        let (offer, offered_amount) = if seat.has_blackjack() {
+            (Offer::EvenMoney, seat.bet)
+        } else {
+            (Offer::Insurance, seat.bet / 2)
+        };
+
+        let offer_message = ServerMessage::Offer {
+            offer,
+            amount: offered_amount,
+        };
+
+        self.send_to_seat(seat_index, &offer_message);
+    }
+
+    /// Closes the insurance decision window of the given seat and takes the insurance stake,
+    /// the stakes are settled at the peek, once all the players decided, so no player learns
+    /// about the bank hole card while the others are still deciding.
+    /// A player holding a natural takes even money with any positive stake, his hand is paid immediately.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    /// `stake` - the insurance stake, 0 if the insurance is declined
+    fn close_insurance(
+        &mut self,
+        seat_index: usize,
+        stake: u32,
+    ) {
+
+        let seat = self.seat_mut(seat_index);
+        seat.insurance_open = false;
+
+        if seat.has_blackjack() && stake > 0 {
+            self.pay_even_money(seat_index);
+        } else {
+
+            seat.insurance_stake = stake
+                .min(seat.bet / 2)
+                .min(seat.bankroll);
+
+            let insurance_stake = seat.insurance_stake;
+
+            if insurance_stake > 0 {
+                self.take_stake(seat_index, insurance_stake);
+            }
+        }
+
+        if !self.is_anyone_deciding() {
+            self.start_player_turn();
+        }
+    }
+
+    /// Pays 1:1 the natural of the given seat taking even money, whatever the bank hole card
+    /// and the bankroll of the player. The hand is settled immediately, it is left out of the round results.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn pay_even_money(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = self.seat_mut(seat_index);
+        seat.settled = true;
+        seat.active_hand = seat.hands.len();
+
+        let stake = seat.hand_bets[0];
+        seat.bankroll = seat.bankroll.saturating_add(stake.saturating_mul(2));
+
+        let result_message = ServerMessage::RoundResult {
+            seat_index: seat_index as u8,
+            hand_index: 0,
+            result: HandResult::Win,
+            amount: stake,
+        };
+
+        self.send_to_seat(seat_index, &result_message);
+        self.send_to_spectators(&result_message);
+        self.send_bankroll(seat_index);
+    }
+
+    /// Settles the pending insurance stakes of all the players, the insurance pays 2:1 if the bank has a blackjack.
+    ///
+    /// # Args:
+    ///
+    /// `bank_blackjack` - true if the bank has a blackjack
+    fn settle_insurances(
+        &mut self,
+        bank_blackjack: bool,
+    ) {
+
+        const INSURANCE_PAYOUT_RATIO: u32 = 2;
+
+        for seat_index in self.round_seats() {
+
+            let insurance_stake = self.seat(seat_index).insurance_stake;
+
+            if insurance_stake == 0 {
+                continue;
+            }
+
+            let returned_amount = if bank_blackjack {
+                insurance_stake.saturating_add(insurance_stake.saturating_mul(INSURANCE_PAYOUT_RATIO))
+            } else {
+                0
+            };
+
+            let result_message = ServerMessage::InsuranceResult {
+                amount: returned_amount,
+            };
+
+            self.send_to_seat(seat_index, &result_message);
+
+            let seat = self.seat_mut(seat_index);
+            seat.insurance_stake = 0;
+            seat.bankroll = seat.bankroll.saturating_add(returned_amount);
+            self.send_bankroll(seat_index);
+        }
+    }
+
+    /// Surrenders the hand of the given seat: half of the bet is lost and the hand is finished.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat surrendering
+    fn surrender(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = self.seat_mut(seat_index);
+        let early_surrender = seat.surrender_open;
+
+        seat.surrender_open = false;
+        seat.surrendered = true;
+        seat.active_hand = seat.hands.len();
+
+        self.send_active_hand(seat_index);
+
+        if !early_surrender {
+            self.next_player_turn(seat_index + 1);
+        }
+        else if !self.is_anyone_deciding() {
+            self.offer_insurance_or_play();
+        }
+    }
+
+    /// Lets the bank peek for a blackjack, settles the insurances and ends the round if it has one,
+    /// otherwise the players play their hands seat by seat. The hands of the players holding a natural
+    /// are finished immediately, a natural beats any other 21 and two naturals push.
+    fn start_player_turn(&mut self) {
+
+        let up_card = self.bank_hand
+            .cards()
+            .first()
+            .unwrap();
+
+        let bank_blackjack = self.rules.dealer_peeks(up_card) &&
+            self.bank_hand.is_blackjack();
+
+        self.settle_insurances(bank_blackjack);
+
+        for seat_index in self.round_seats() {
+
+            let seat = self.seat_mut(seat_index);
+
+            if bank_blackjack || seat.has_blackjack() {
+                seat.active_hand = seat.hands.len();
+            }
+        }
+
+        self.next_player_turn(0);
+    }
+
+    /// Ends the active hand of the given seat and moves to its next one.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn finish_active_hand(
+        &mut self,
+        seat_index: usize,
+    ) {
+        self.seat_mut(seat_index).active_hand += 1;
+        self.update_player_turn(seat_index);
+    }
+
+    /// Welcomes the player sitting at the given seat with his identifier, the seat index
+    /// so he can recognize his own cards, the table rules so he can apply the same rules as the server,
+    /// his bankroll and the amount of cards remaining into the shoe.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn send_welcome(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = self.seat(seat_index);
+
+        let welcome_message = ServerMessage::Welcome {
+            player_id: seat.player_id,
+            seat_index: seat_index as u8,
+            rules: self.rules.clone(),
+            bankroll: seat.bankroll,
+            cards_amount: self.shoe.len() as u16,
+        };
+
+        self.send_to_seat(seat_index, &welcome_message);
+    }
+
+    /// Sends his session token to the player of the given seat, in order to resume his session
+    /// if he gets disconnected.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn send_session(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let session_message = ServerMessage::Session {
+            session_token: self.seat(seat_index).session_token.clone(),
+        };
+
+        self.send_to_seat(seat_index, &session_message);
+    }
+
+    /// Sends the whole current state of the table to a player resuming his session:
+    /// the welcome with his seat, the rules and his bankroll, the snapshot of the table
+    /// and, if a round is in progress, his pending offer.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat of the resuming player
+    fn send_snapshot(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        self.send_welcome(seat_index);
+        self.send_to_seat(seat_index, &self.snapshot_message());
+
+        let seat = self.seat(seat_index);
+
+        /* the even money offer amount is the bet, the other offers amount is half of the bet */
+        let pending_offer = if seat.surrender_open {
+            Some((Offer::Surrender, seat.bet / 2))
+        } else if seat.insurance_open && seat.has_blackjack() {
+            Some((Offer::EvenMoney, seat.bet))
+        } else if seat.insurance_open {
+            Some((Offer::Insurance, seat.bet / 2))
+        } else {
+            None
+        };
+
+        if let Some((offer, amount)) = pending_offer {
+
+            let offer_message = ServerMessage::Offer {
+                offer,
+                amount,
+            };
+
+            self.send_to_seat(seat_index, &offer_message);
+        }
+    }
+
+    /// Draws one face-up card of the bank, and render the card on the players side.
+    /// Returns false if no card is left to draw.
+    fn draw_one_bank_card(&mut self) -> bool {
+
+        let card = match self.draw_one_card() {
+            Some(card) => card,
+            None => return false,
+        };
+        self.bank_hand.add(card);
+
+        let card_message = ServerMessage::BankCard {
+            card: card.wire_id(),
+            points: self.bank_hand.soft_total(),
+            cards_amount: self.shoe.len() as u16,
+        };
+
+        self.broadcast(&card_message);
+
+        true
+    }
+
+    /// Draws the face-down card of the bank. The players are only notified a card has been drawn,
+    /// the card itself is withheld until the bank cards are revealed.
+    fn draw_hole_card(&mut self) {
+
+        let card = match self.draw_one_card() {
+            Some(card) => card,
+            None => return,
+        };
+        self.bank_hand.add(card);
+
+        let hole_card_message = ServerMessage::HoleCard {
+            cards_amount: self.shoe.len() as u16,
+        };
+
+        self.broadcast(&hole_card_message);
+    }
+
+    /// Draws all the remaining cards of the bank, once its two first cards are revealed.
+    /// Every card is sent with the remaining cards amount, so the players can verify it like their own ones.
+    /// The bank stands if no card is left to draw.
+    fn draw_all_bank_cards(&mut self) {
+
+        while self.rules.dealer_must_hit(&self.bank_hand) &&
+            self.draw_one_bank_card() {}
+    }
+
+    /// Ends the round: sends the results of every player against the revealed bank cards and pays them,
+    /// then discards the cards of the round. The players have to bet again for the next round.
+    fn finish_round(&mut self) {
+
+        self.phase = RoundPhase::Settlement;
+
+        let round_seats = self.round_seats();
+
+        for seat_index in &round_seats {
+            self.send_round_results(*seat_index);
+        }
+
+        self.discard_round_cards();
+
+        for seat_index in round_seats {
+
+            let seat = self.seat_mut(seat_index);
+            seat.in_round = false;
+            seat.bet = 0;
+        }
+
+        self.phase = RoundPhase::Betting;
+    }
+
+    /// Reveals all the bank cards (including the hole card) to the players.
+    fn send_bank_cards(&mut self) {
+
+        let cards_message = ServerMessage::BankCards {
+            cards: self.bank_hand
+                .cards()
+                .iter()
+                .map(|card| card.wire_id())
+                .collect(),
+            points: self.bank_hand.soft_total(),
+        };
+
+        self.broadcast(&cards_message);
+    }
+
+    /// Sends the result of every hand of the given seat against the bank hand, with the amount won or lost,
+    /// to the player and the spectators, then pays the player: the stakes of the hands that are not lost
+    /// come back with the winnings. The hand paid with even money has already been settled.
+    ///
+    /// # Args:
+    ///
+    /// `seat_index` - the index of the seat
+    fn send_round_results(
+        &mut self,
+        seat_index: usize,
+    ) {
+
+        let seat = self.seat(seat_index);
+
+        if seat.settled {
+            return;
+        }
+        let mut returned_amount: u32 = 0;
+
+        for (hand_index, hand) in seat.hands.iter().enumerate() {
+
+            let result = HandResult::of(
+                hand,
+                &self.bank_hand,
+                seat.surrendered,
+            );
+
+            let stake = seat.hand_bets[hand_index];
+            let amount = result.amount(
+                stake,
+                self.rules.blackjack_payout,
+            );
+
+            returned_amount = returned_amount.saturating_add(
+                if result.is_loss() {
+                    stake - amount
+                } else {
+                    stake.saturating_add(amount)
+                }
+            );
+
+            let result_message = ServerMessage::RoundResult {
+                seat_index: seat_index as u8,
+                hand_index: hand_index as u8,
+                result,
+                amount,
+            };
+
+            self.send_to_seat(seat_index, &result_message);
+            self.send_to_spectators(&result_message);
+        }
+
+        let seat = self.seat_mut(seat_index);
+        seat.bankroll = seat.bankroll.saturating_add(returned_amount);
+        self.send_bankroll(seat_index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use blackjack_core::RandomShuffle;
+
+    /// Returns the wire ids of the cards dealt by a new table started with the given seed,
+    /// over its two first shoes.
+    ///
+    /// # Args:
+    ///
+    /// `table_seed` - the seed of the table
+    fn dealt_cards(table_seed: u64) -> Vec<u16> {
+
+        const DEALT_CARDS_AMOUNT: usize = 20;
+
+        let mut table = Table::new(
+            "Seeded table".to_string(),
+            TableRules::default(),
+            Some(table_seed),
+            vec![],
+            Box::new(RandomShuffle),
+        );
+
+        let mut cards: Vec<u16> = (0..DEALT_CARDS_AMOUNT)
+            .filter_map(|_| table.draw_one_card())
+            .map(|card| card.wire_id())
+            .collect();
+
+        table.shuffle_cards();
+
+        cards.extend(
+            (0..DEALT_CARDS_AMOUNT)
+                .filter_map(|_| table.draw_one_card())
+                .map(|card| card.wire_id())
+        );
+
+        cards
+    }
+
+    #[test]
+    fn test_seeded_tables_deal_the_same_cards() {
+
+        const TABLE_SEED: u64 = 42;
+
+        let cards = dealt_cards(TABLE_SEED);
+
+        assert_eq!(cards.len(), 40);
+        assert_eq!(cards, dealt_cards(TABLE_SEED));
+        assert_ne!(cards, dealt_cards(TABLE_SEED + 1));
+    }
+
+    #[test]
+    fn test_replayed_client_seeds_deal_the_same_cards() {
+
+        let client_seeds = vec![
+            vec![],
+            vec!["1718f2a3c5e0b4d1".to_string()],
+        ];
+
+        let shoe_orders: Vec<Vec<u16>> = (0..2)
+            .map(|_| {
+
+                let mut table = Table::new(
+                    "Replayed table".to_string(),
+                    TableRules::default(),
+                    Some(42),
+                    client_seeds.clone(),
+                    Box::new(RandomShuffle),
+                );
+                table.shuffle_cards();
+
+                assert_eq!(table.client_seeds, client_seeds[1]);
+
+                table.shoe_order.clone()
+            })
+            .collect();
+
+        assert_eq!(shoe_orders[0], shoe_orders[1]);
+    }
+}